@@ -0,0 +1,574 @@
+use std::{str::FromStr, time::Duration};
+
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    sync::oneshot,
+};
+use tracing::{info, warn};
+
+use crate::{
+    canvas::{self, Canvas, DEFAULT_CANVAS},
+    connection::Context,
+    generated::grid::{Color, NOTICE_ANNOUNCEMENT, NOTICE_WARNING},
+    history::RollbackFilter,
+    moderation::{unix_now, unix_now_millis, Ban, BanTarget, Sanction},
+    place::{GridCommand, LayerSettings, Region},
+    protocol,
+    regions::ProtectedRegion,
+    templates::Template,
+};
+
+/// Largest width or height a canvas can have.
+pub const MAX_GRID_SIZE: u32 = 4096;
+
+/// Something a moderator asked for, parsed from a line like `ban 10.0.0.0/8 7d spam`.
+#[derive(Debug)]
+pub enum AdminCommand {
+    Ban(Ban),
+    Unban(BanTarget),
+    ListBans,
+    CreateCanvas {
+        name: String,
+        width: u32,
+        height: u32,
+    },
+    DeleteCanvas(String),
+    ListCanvases,
+    /// Shows the text to the clients of every canvas, e.g. before maintenance.
+    Announce(String),
+    /// A command for one canvas, the default one unless the line starts with `on <canvas>`.
+    Canvas {
+        canvas: String,
+        command: CanvasCommand,
+    },
+}
+
+/// A command changing one canvas, applied by its grid manipulator.
+#[derive(Debug)]
+pub enum CanvasCommand {
+    Rollback(RollbackFilter),
+    Resize {
+        width: u32,
+        height: u32,
+    },
+    /// Refuses every placement while `true`.
+    Lock(bool),
+    /// Opens and closes the canvas at these Unix times, `None` leaving that end open.
+    Schedule {
+        starts_at: Option<u64>,
+        ends_at: Option<u64>,
+    },
+    /// Paints the region of the grid back to white, or makes it transparent on a layer
+    /// over the grid.
+    Clear {
+        region: Region,
+        layer: Option<String>,
+    },
+    /// Paints one pixel of the grid or of a layer, even a restricted one.
+    Paint {
+        x: u32,
+        y: u32,
+        color: Option<Color>,
+        layer: Option<String>,
+    },
+    /// Stacks a blank layer on top of the others.
+    AddLayer(LayerSettings),
+    RemoveLayer(String),
+    /// Keeps everyone but moderators from painting the layer while `true`.
+    RestrictLayer {
+        name: String,
+        restricted: bool,
+    },
+    ListLayers,
+    /// Adds or replaces a template, uploaded over the admin channel.
+    AddTemplate(Template),
+    RemoveTemplate(String),
+    ListTemplates,
+    Protect(ProtectedRegion),
+    /// Lifts the protection of the named region.
+    Unprotect(String),
+    ListRegions,
+    /// Restricts placements to these colors, an empty palette allows any color.
+    Palette(Vec<Color>),
+    /// Sets the minimum time between two placements of a session, `None` going back to
+    /// `PLACE_COOLDOWN_MS`.
+    Cooldown(Option<Duration>),
+}
+
+impl FromStr for AdminCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace().peekable();
+        let command = words.next().ok_or("empty command")?;
+        match command {
+            "ban" | "mute" => {
+                let target = words.next().ok_or("missing target")?.parse()?;
+                let duration = match words.peek() {
+                    Some(word) => parse_duration(word)?,
+                    None => None,
+                };
+                if duration.is_some() {
+                    words.next();
+                }
+                let expires_at = duration.map(from_now).transpose()?;
+                let reason = words.collect::<Vec<_>>().join(" ");
+                Ok(AdminCommand::Ban(Ban {
+                    target,
+                    sanction: match command {
+                        "ban" => Sanction::Ban,
+                        _ => Sanction::Mute,
+                    },
+                    expires_at,
+                    reason: (!reason.is_empty()).then_some(reason),
+                }))
+            }
+            "unban" | "unmute" => Ok(AdminCommand::Unban(
+                words.next().ok_or("missing target")?.parse()?,
+            )),
+            "bans" => Ok(AdminCommand::ListBans),
+            "canvas" => {
+                let action = words.next().ok_or("canvas needs create or delete")?;
+                let name = parse_name(words.next(), "canvas")?;
+                match action {
+                    "create" => {
+                        let (width, height) = parse_size(&mut words)?;
+                        Ok(AdminCommand::CreateCanvas {
+                            name,
+                            width,
+                            height,
+                        })
+                    }
+                    "delete" => Ok(AdminCommand::DeleteCanvas(name)),
+                    _ => Err(format!(
+                        "unknown canvas action {:?}, expected create or delete",
+                        action
+                    )),
+                }
+            }
+            "canvases" => Ok(AdminCommand::ListCanvases),
+            "announce" => {
+                let text = words.collect::<Vec<_>>().join(" ");
+                if text.is_empty() {
+                    return Err("announce needs a text".to_string());
+                }
+                Ok(AdminCommand::Announce(text))
+            }
+            "on" => {
+                let canvas = parse_name(words.next(), "canvas")?;
+                let command = words.collect::<Vec<_>>().join(" ").parse()?;
+                Ok(AdminCommand::Canvas { canvas, command })
+            }
+            _ => Ok(AdminCommand::Canvas {
+                canvas: DEFAULT_CANVAS.to_string(),
+                command: line.parse()?,
+            }),
+        }
+    }
+}
+
+impl FromStr for CanvasCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut words = line.split_whitespace().peekable();
+        let command = words.next().ok_or("empty command")?;
+        match command {
+            "rollback" => parse_rollback(words).map(CanvasCommand::Rollback),
+            "resize" => {
+                let (width, height) = parse_size(&mut words)?;
+                Ok(CanvasCommand::Resize { width, height })
+            }
+            "lock" => Ok(CanvasCommand::Lock(true)),
+            "unlock" => Ok(CanvasCommand::Lock(false)),
+            "schedule" => {
+                let mut time = || parse_time(words.next().ok_or("schedule needs <start> <end>")?);
+                Ok(CanvasCommand::Schedule {
+                    starts_at: time()?,
+                    ends_at: time()?,
+                })
+            }
+            "clear" => Ok(CanvasCommand::Clear {
+                region: parse_region(&mut words)?,
+                layer: words.next().map(str::to_string),
+            }),
+            "paint" => {
+                let mut coordinate = || -> Result<u32, String> {
+                    words
+                        .next()
+                        .and_then(|word| word.parse().ok())
+                        .ok_or_else(|| "paint needs <x> <y> <color>".to_string())
+                };
+                let (x, y) = (coordinate()?, coordinate()?);
+                let color = match words.next().ok_or("paint needs <x> <y> <color>")? {
+                    "none" => None,
+                    color => Some(color.parse()?),
+                };
+                Ok(CanvasCommand::Paint {
+                    x,
+                    y,
+                    color,
+                    layer: words.next().map(str::to_string),
+                })
+            }
+            "layer" => {
+                let action = words
+                    .next()
+                    .ok_or("layer needs add, remove, restrict or open")?;
+                let name = parse_name(words.next(), "layer")?;
+                match action {
+                    "add" => Ok(CanvasCommand::AddLayer(LayerSettings {
+                        name,
+                        restricted: words.next() == Some("restricted"),
+                    })),
+                    "remove" => Ok(CanvasCommand::RemoveLayer(name)),
+                    "restrict" | "open" => Ok(CanvasCommand::RestrictLayer {
+                        name,
+                        restricted: action == "restrict",
+                    }),
+                    _ => Err(format!(
+                        "unknown layer action {:?}, expected add, remove, restrict or open",
+                        action
+                    )),
+                }
+            }
+            "layers" => Ok(CanvasCommand::ListLayers),
+            "template" => match words.next() {
+                Some("remove") => Ok(CanvasCommand::RemoveTemplate(parse_name(
+                    words.next(),
+                    "template",
+                )?)),
+                Some(action) => Err(format!(
+                    "unknown template action {:?}, templates are added with a TemplateUpload \
+                     message and removed with template remove <name>",
+                    action
+                )),
+                None => Err("template needs remove".to_string()),
+            },
+            "templates" => Ok(CanvasCommand::ListTemplates),
+            "protect" => {
+                let name = words.next().ok_or("missing region name")?.to_string();
+                let region = parse_region(&mut words)?;
+                let grantees = words.map(str::parse).collect::<Result<_, _>>()?;
+                Ok(CanvasCommand::Protect(ProtectedRegion {
+                    name,
+                    region,
+                    grantees,
+                }))
+            }
+            "unprotect" => Ok(CanvasCommand::Unprotect(
+                words.next().ok_or("missing region name")?.to_string(),
+            )),
+            "regions" => Ok(CanvasCommand::ListRegions),
+            "palette" => match words.peek() {
+                Some(&"any") => Ok(CanvasCommand::Palette(Vec::new())),
+                Some(_) => words
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map(CanvasCommand::Palette),
+                None => Err("palette needs colors like ff4500, or any".to_string()),
+            },
+            "cooldown" => match words.next() {
+                Some("default") => Ok(CanvasCommand::Cooldown(None)),
+                Some(word) => match parse_duration(word)? {
+                    Some(cooldown) => Ok(CanvasCommand::Cooldown(Some(cooldown))),
+                    None => Err(format!("{:?} is not a duration like 30s, or default", word)),
+                },
+                None => Err("cooldown needs a duration like 30s, or default".to_string()),
+            },
+            _ => Err(format!(
+                "unknown command {:?}, expected ban, mute, unban, unmute, bans, canvas, \
+                 canvases, announce, on, rollback, resize, lock, unlock, schedule, clear, paint, \
+                 protect, unprotect, regions, palette, cooldown, layer, layers, template or \
+                 templates",
+                command
+            )),
+        }
+    }
+}
+
+/// Parses the name of a canvas or a layer, `what` telling which in errors.
+fn parse_name(word: Option<&str>, what: &str) -> Result<String, String> {
+    match word {
+        Some(name) if canvas::is_valid_name(name) => Ok(name.to_string()),
+        Some(name) => Err(format!(
+            "{:?} is not a {} name, use up to 32 letters, digits, - or _",
+            name, what
+        )),
+        None => Err(format!("missing {} name", what)),
+    }
+}
+
+/// Parses `<width> <height>`.
+fn parse_size<'a>(words: &mut impl Iterator<Item = &'a str>) -> Result<(u32, u32), String> {
+    let mut size = || {
+        words
+            .next()
+            .and_then(|word| word.parse().ok())
+            .filter(|size| (1..=MAX_GRID_SIZE).contains(size))
+            .ok_or_else(|| {
+                format!(
+                    "expected a width and a height between 1 and {}",
+                    MAX_GRID_SIZE
+                )
+            })
+    };
+    Ok((size()?, size()?))
+}
+
+/// Parses `[by <target>] [since <duration>] [until <duration>] [region <x> <y> <w> <h>]`,
+/// durations counting back from now.
+fn parse_rollback<'a>(mut words: impl Iterator<Item = &'a str>) -> Result<RollbackFilter, String> {
+    let mut filter = RollbackFilter::default();
+    let ago = |word: Option<&str>| -> Result<u64, String> {
+        let duration = word
+            .map(parse_duration)
+            .transpose()?
+            .flatten()
+            .ok_or("expected a duration like 30m")?;
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        Ok(unix_now_millis().saturating_sub(millis))
+    };
+    while let Some(word) = words.next() {
+        match word {
+            "by" => filter.placer = Some(words.next().ok_or("missing target")?.parse()?),
+            "since" => filter.since = Some(ago(words.next())?),
+            "until" => filter.until = Some(ago(words.next())?),
+            "region" => filter.region = Some(parse_region(&mut words)?),
+            _ => return Err(format!("unexpected {:?} in rollback", word)),
+        }
+    }
+    if filter.is_empty() {
+        return Err("refusing to roll back every placement, add by, since, until or region".into());
+    }
+    Ok(filter)
+}
+
+/// Parses `<x> <y> <width> <height>`.
+fn parse_region<'a>(words: &mut impl Iterator<Item = &'a str>) -> Result<Region, String> {
+    let mut next = || -> Result<u32, String> {
+        words
+            .next()
+            .and_then(|word| word.parse().ok())
+            .ok_or_else(|| "a region needs <x> <y> <width> <height>".to_string())
+    };
+    Ok(Region {
+        x: next()?,
+        y: next()?,
+        width: next()?,
+        height: next()?,
+    })
+}
+
+/// Parses durations like `90s`, `15m`, `12h` or `7d`, `None` when the word isn't one.
+fn parse_duration(word: &str) -> Result<Option<Duration>, String> {
+    let unit = match word.chars().last() {
+        Some('s') => 1,
+        Some('m') => 60,
+        Some('h') => 60 * 60,
+        Some('d') => 24 * 60 * 60,
+        _ => return Ok(None),
+    };
+    let amount = &word[..word.len() - 1];
+    if amount.is_empty() || !amount.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Ok(None);
+    }
+    amount
+        .parse::<u64>()
+        .ok()
+        .and_then(|amount| amount.checked_mul(unit))
+        .map(|secs| Some(Duration::from_secs(secs)))
+        .ok_or_else(|| format!("{:?} is too long", word))
+}
+
+/// The Unix time in seconds once `duration` passed from now.
+fn from_now(duration: Duration) -> Result<u64, String> {
+    unix_now()
+        .checked_add(duration.as_secs())
+        .ok_or_else(|| format!("{}s from now is too far", duration.as_secs()))
+}
+
+/// Parses a Unix time in seconds, a duration from now like `+2h`, or `-` for none.
+fn parse_time(word: &str) -> Result<Option<u64>, String> {
+    if word == "-" {
+        return Ok(None);
+    }
+    let time = match word.strip_prefix('+') {
+        Some(duration) => parse_duration(duration)?.map(from_now).transpose()?,
+        None => word.parse().ok(),
+    };
+    time.map(Some)
+        .ok_or_else(|| format!("{:?} is not a Unix time, a duration like +2h or -", word))
+}
+
+/// Applies the command, describing the outcome or why it failed.
+pub async fn execute(command: AdminCommand, context: &Context) -> Result<String, String> {
+    match command {
+        AdminCommand::Ban(ban) => {
+            let description = ban.to_string();
+            context.bans.write().unwrap().add(ban).map_err(|err| {
+                format!(
+                    "{}, but the ban list could not be saved: {}",
+                    description, err
+                )
+            })?;
+            Ok(description)
+        }
+        AdminCommand::Unban(target) => match context.bans.write().unwrap().remove(target) {
+            Ok(true) => Ok(format!("{} is no longer banned", target)),
+            Ok(false) => Err(format!("{} was not banned", target)),
+            Err(err) => Err(format!(
+                "lifted the ban but could not save the list: {}",
+                err
+            )),
+        },
+        AdminCommand::ListBans => {
+            let bans = context.bans.read().unwrap();
+            let lines: Vec<String> = bans.iter().map(Ban::to_string).collect();
+            if lines.is_empty() {
+                Ok("nobody is banned".to_string())
+            } else {
+                Ok(lines.join("\n"))
+            }
+        }
+        AdminCommand::CreateCanvas {
+            name,
+            width,
+            height,
+        } => match context.canvases.create(&name, width, height).await {
+            Ok(true) => Ok(format!("created canvas {} of {}x{}", name, width, height)),
+            Ok(false) => Err(format!("canvas {} already exists", name)),
+            Err(err) => Err(format!("could not create canvas {}: {}", name, err)),
+        },
+        AdminCommand::DeleteCanvas(name) => match context.canvases.delete(&name).await {
+            Ok(true) => Ok(format!("deleted canvas {}", name)),
+            Ok(false) => Err(format!("no canvas is named {}", name)),
+            Err(err) => Err(format!(
+                "stopped canvas {} but could not delete its files: {}",
+                name, err
+            )),
+        },
+        AdminCommand::ListCanvases => {
+            let mut lines = Vec::new();
+            for canvas in context.canvases.list() {
+                let state_guard = canvas.state.read().await;
+                lines.push(format!(
+                    "{} {}x{}, {}",
+                    canvas.name,
+                    state_guard.get_grid_width(),
+                    state_guard.get_grid_height(),
+                    state_guard.get_schedule().status(unix_now())
+                ));
+            }
+            if lines.is_empty() {
+                Ok("there is no canvas".to_string())
+            } else {
+                Ok(lines.join("\n"))
+            }
+        }
+        AdminCommand::Announce(text) => {
+            let notice = protocol::encode_notice(NOTICE_ANNOUNCEMENT, NOTICE_WARNING, &text);
+            let reached = context.canvases.broadcast(notice);
+            info!(clients = reached, %text, "announced");
+            Ok(format!("announced to {} clients", reached))
+        }
+        AdminCommand::Canvas { canvas, command } => {
+            let canvas = context
+                .canvases
+                .get(&canvas)
+                .ok_or_else(|| format!("no canvas is named {}", canvas))?;
+            execute_on(&canvas, command).await
+        }
+    }
+}
+
+/// Queues the command behind the pending placements of the canvas and waits for its grid
+/// manipulator to apply it.
+pub async fn execute_on(canvas: &Canvas, command: CanvasCommand) -> Result<String, String> {
+    let stopped = || format!("canvas {} has stopped", canvas.name);
+    let (reply, response) = oneshot::channel();
+    canvas
+        .sender_grid_manipulator
+        .send(GridCommand::Admin { command, reply })
+        .await
+        .map_err(|_| stopped())?;
+    response.await.map_err(|_| stopped())?
+}
+
+/// Reads admin commands from the server's standard input, one per line.
+pub async fn console(context: Context) {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(err) => {
+                warn!(error = %err, "could not read from the console");
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let result = match line.parse() {
+            Ok(command) => {
+                info!(command = %line.trim(), "running admin command");
+                execute(command, &context).await
+            }
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(message) => println!("{}", message),
+            Err(message) => println!("error: {}", message),
+        }
+    }
+    info!("console closed, admin commands are no longer read");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90s"), Ok(Some(Duration::from_secs(90))));
+        assert_eq!(
+            parse_duration("2d"),
+            Ok(Some(Duration::from_secs(2 * 24 * 60 * 60)))
+        );
+        assert_eq!(parse_duration("spam"), Ok(None));
+        assert_eq!(parse_duration("d"), Ok(None));
+        assert!(parse_duration("999999999999999999d").is_err());
+        assert!(parse_duration("99999999999999999999999s").is_err());
+    }
+
+    #[test]
+    fn bans_expire_after_their_duration() {
+        let now = unix_now();
+        match "ban 127.0.0.1 1h spamming".parse() {
+            Ok(AdminCommand::Ban(ban)) => {
+                assert!((now + 3600..=unix_now() + 3600).contains(&ban.expires_at.unwrap()));
+                assert_eq!(ban.reason.as_deref(), Some("spamming"));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_schedule_times() {
+        assert_eq!(parse_time("-"), Ok(None));
+        assert_eq!(parse_time("1700000000"), Ok(Some(1700000000)));
+        let in_two_hours = parse_time("+2h").unwrap().unwrap();
+        assert!(in_two_hours >= unix_now() + 2 * 60 * 60);
+        assert!(parse_time("+213503982334601d").is_err());
+        assert!(parse_time("soon").is_err());
+    }
+
+    #[test]
+    fn refuses_bans_too_long_to_expire() {
+        let parsed: Result<AdminCommand, _> = "ban 127.0.0.1 213503982334601d".parse();
+        assert!(parsed.is_err());
+        let parsed: Result<AdminCommand, _> = "ban 127.0.0.1 999999999999999999d".parse();
+        assert!(parsed.is_err());
+    }
+}
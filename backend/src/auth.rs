@@ -0,0 +1,248 @@
+use std::{error, fmt, fs, io, path::Path};
+
+use jsonwebtoken::{
+    decode, decode_header,
+    errors::Error as JwtError,
+    jwk::{Jwk, JwkSet},
+    DecodingKey, Validation,
+};
+use serde::Deserialize;
+
+/// Who a verified token belongs to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identity {
+    /// Stable id of the user at the identity provider, the `sub` claim of a JWT.
+    pub subject: String,
+    /// Groups the user belongs to, like a team or a sponsor, from the `roles` claim.
+    pub roles: Vec<String>,
+}
+
+/// Why a bearer token was refused.
+#[derive(Debug)]
+pub enum AuthError {
+    /// The token names a signing key that isn't in the key set.
+    UnknownKey(Option<String>),
+    /// The token is malformed, badly signed, expired or meant for someone else.
+    Invalid(JwtError),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::UnknownKey(Some(kid)) => write!(f, "unknown signing key {:?}", kid),
+            AuthError::UnknownKey(None) => write!(f, "token doesn't say which key signed it"),
+            AuthError::Invalid(err) => write!(f, "invalid token: {}", err),
+        }
+    }
+}
+
+impl error::Error for AuthError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            AuthError::UnknownKey(_) => None,
+            AuthError::Invalid(err) => Some(err),
+        }
+    }
+}
+
+impl From<JwtError> for AuthError {
+    fn from(err: JwtError) -> Self {
+        AuthError::Invalid(err)
+    }
+}
+
+/// Checks the bearer tokens clients present during the WebSocket handshake.
+///
+/// Runs inside the handshake callback, so implementations must not block for long.
+pub trait Verifier: Send + Sync {
+    fn verify(&self, token: &str) -> Result<Identity, AuthError>;
+}
+
+/// Verifies JWTs signed by one of the keys of a JSON Web Key Set read from disk.
+pub struct JwksVerifier {
+    keys: JwkSet,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    sub: String,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+impl JwksVerifier {
+    /// Reads the key set, tokens must come from `issuer` and be meant for `audience` when given.
+    pub fn load(
+        path: impl AsRef<Path>,
+        issuer: Option<String>,
+        audience: Option<String>,
+    ) -> io::Result<Self> {
+        let keys = serde_json::from_slice(&fs::read(path)?)?;
+        Ok(JwksVerifier {
+            keys,
+            issuer,
+            audience,
+        })
+    }
+
+    fn find_key(&self, kid: Option<&str>) -> Option<&Jwk> {
+        match kid {
+            Some(kid) => self.keys.find(kid),
+            // Tokens may leave out the key id when the provider only has one key.
+            None if self.keys.keys.len() == 1 => self.keys.keys.first(),
+            None => None,
+        }
+    }
+}
+
+impl Verifier for JwksVerifier {
+    fn verify(&self, token: &str) -> Result<Identity, AuthError> {
+        let header = decode_header(token)?;
+        let key = self
+            .find_key(header.kid.as_deref())
+            .ok_or_else(|| AuthError::UnknownKey(header.kid.clone()))?;
+
+        // The key decides the algorithm when it says so, a token can't pick a weaker one.
+        let algorithm = key.common.algorithm.unwrap_or(header.alg);
+        let mut validation = Validation::new(algorithm);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        if let Some(audience) = &self.audience {
+            validation.set_audience(&[audience]);
+        }
+
+        let claims = decode::<Claims>(token, &DecodingKey::from_jwk(key)?, &validation)?.claims;
+        Ok(Identity {
+            subject: claims.sub,
+            roles: claims.roles,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use rand::{distributions::Slice, Rng};
+    use serde_json::json;
+
+    use super::*;
+    use crate::moderation::unix_now;
+
+    /// A fresh HMAC secret, as the base64 a JWK holds.
+    fn secret() -> String {
+        let alphabet: Vec<char> = ('A'..='Z')
+            .chain('a'..='z')
+            .chain('0'..='9')
+            .chain(['+', '/'])
+            .collect();
+        let alphabet = Slice::new(&alphabet).unwrap();
+        rand::thread_rng().sample_iter(alphabet).take(44).collect()
+    }
+
+    fn verifier(kid: &str, secret: &str) -> JwksVerifier {
+        let keys = json!({
+            "keys": [{ "kty": "oct", "kid": kid, "alg": "HS256", "k": secret }]
+        });
+        JwksVerifier {
+            keys: serde_json::from_value(keys).unwrap(),
+            issuer: Some("https://id.example.com".to_string()),
+            audience: Some("place".to_string()),
+        }
+    }
+
+    fn token(alg: Algorithm, kid: Option<&str>, secret: &str, claims: serde_json::Value) -> String {
+        let header = Header {
+            kid: kid.map(str::to_string),
+            ..Header::new(alg)
+        };
+        let key = EncodingKey::from_base64_secret(secret).unwrap();
+        encode(&header, &claims, &key).unwrap()
+    }
+
+    fn claims() -> serde_json::Value {
+        json!({
+            "sub": "alice",
+            "roles": ["sponsor"],
+            "iss": "https://id.example.com",
+            "aud": "place",
+            "exp": unix_now() + 600,
+        })
+    }
+
+    #[test]
+    fn verifies_tokens_signed_with_a_known_key() {
+        let secret = secret();
+        let verifier = verifier("main", &secret);
+        let identity = verifier
+            .verify(&token(Algorithm::HS256, Some("main"), &secret, claims()))
+            .unwrap();
+        assert_eq!(
+            identity,
+            Identity {
+                subject: "alice".to_string(),
+                roles: vec!["sponsor".to_string()],
+            }
+        );
+        // The only key of the set is used for tokens that don't name one.
+        assert!(verifier
+            .verify(&token(Algorithm::HS256, None, &secret, claims()))
+            .is_ok());
+    }
+
+    #[test]
+    fn refuses_expired_tokens() {
+        let secret = secret();
+        let mut claims = claims();
+        claims["exp"] = json!(unix_now() - 600);
+        let token = token(Algorithm::HS256, Some("main"), &secret, claims);
+        assert!(matches!(
+            verifier("main", &secret).verify(&token),
+            Err(AuthError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn refuses_tokens_from_another_issuer_or_for_another_audience() {
+        let secret = secret();
+        let verifier = verifier("main", &secret);
+        for (claim, value) in [("iss", "https://evil.example.com"), ("aud", "chat")] {
+            let mut claims = claims();
+            claims[claim] = json!(value);
+            let token = token(Algorithm::HS256, Some("main"), &secret, claims);
+            assert!(matches!(
+                verifier.verify(&token),
+                Err(AuthError::Invalid(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn refuses_tokens_signed_with_an_unknown_key() {
+        let secret = secret();
+        let verifier = verifier("main", &secret);
+        let unknown = token(Algorithm::HS256, Some("old"), &secret, claims());
+        assert!(matches!(
+            verifier.verify(&unknown),
+            Err(AuthError::UnknownKey(Some(kid))) if kid == "old"
+        ));
+        let other_secret = self::secret();
+        let forged = token(Algorithm::HS256, Some("main"), &other_secret, claims());
+        assert!(matches!(
+            verifier.verify(&forged),
+            Err(AuthError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn refuses_tokens_picking_another_algorithm_than_their_key() {
+        let secret = secret();
+        let token = token(Algorithm::HS512, Some("main"), &secret, claims());
+        assert!(matches!(
+            verifier("main", &secret).verify(&token),
+            Err(AuthError::Invalid(_))
+        ));
+    }
+}
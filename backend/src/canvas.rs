@@ -0,0 +1,307 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{self, Arc},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tracing::{debug, info, info_span, warn, Instrument};
+
+use crate::{
+    config::Config,
+    generated::grid::Color,
+    history::History,
+    manipulator::GridManipulator,
+    metrics::Metrics,
+    moderation::BanList,
+    place::{Cooldowns, GridCommand, LayerSettings, Schedule, State},
+    regions::ProtectedRegions,
+    storage, templates,
+};
+
+/// Canvas served on `/`, created with the default size when there is none yet.
+pub const DEFAULT_CANVAS: &str = "default";
+
+/// What's kept of a canvas across restarts, its pixels and those of its layers aside.
+#[derive(Serialize, Deserialize)]
+pub struct CanvasSettings {
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub palette: Vec<Color>,
+    #[serde(default)]
+    pub schedule: Schedule,
+    /// Bottom first, the grid alone when empty.
+    #[serde(default)]
+    pub layers: Vec<LayerSettings>,
+    /// Minimum time between two placements of a session, in milliseconds. `None` follows
+    /// `PLACE_COOLDOWN_MS`.
+    #[serde(default)]
+    pub cooldown_ms: Option<u64>,
+}
+
+impl CanvasSettings {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+}
+
+/// Whether the name can be used in a `/ws/{canvas}` path and as a directory name.
+pub fn is_valid_name(name: &str) -> bool {
+    (1..=32).contains(&name.len())
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+}
+
+/// Handles to a running canvas, each with its own grid manipulator.
+#[derive(Clone)]
+pub struct Canvas {
+    pub name: String,
+    pub state: Arc<RwLock<State>>,
+    pub sender_grid_manipulator: mpsc::Sender<GridCommand>,
+}
+
+struct Entry {
+    canvas: Canvas,
+    /// Only kept here and by the grid manipulator, so the clients' receivers close once
+    /// the canvas is deleted and its manipulator stopped.
+    sender_clients: broadcast::Sender<Vec<u8>>,
+}
+
+/// Every canvas served by the process, by name.
+///
+/// Each one lives in its own directory under `canvases` in the data directory, holding
+/// its settings, protected regions and templates.
+pub struct Canvases {
+    entries: sync::RwLock<HashMap<String, Entry>>,
+    /// Names of the canvases being created, taken until they're started or failed to.
+    creating: sync::Mutex<HashSet<String>>,
+    config: Arc<Config>,
+    bans: Arc<sync::RwLock<BanList>>,
+    metrics: Arc<Metrics>,
+}
+
+impl Canvases {
+    /// Starts the canvases saved in the data directory, or a default one on the first start.
+    pub async fn load(
+        config: Arc<Config>,
+        bans: Arc<sync::RwLock<BanList>>,
+        metrics: Arc<Metrics>,
+    ) -> io::Result<Self> {
+        let canvases = Canvases {
+            entries: sync::RwLock::new(HashMap::new()),
+            creating: sync::Mutex::new(HashSet::new()),
+            config,
+            bans,
+            metrics,
+        };
+        let dir = canvases.config.data_dir.join("canvases");
+        fs::create_dir_all(&dir)?;
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !is_valid_name(&name) || !entry.file_type()?.is_dir() {
+                warn!(path = %entry.path().display(), "skipping stray file");
+                continue;
+            }
+            let settings = CanvasSettings::load(&entry.path().join("canvas.json"))?;
+            canvases.start(name, settings).await?;
+        }
+        if canvases.entries.read().unwrap().is_empty() {
+            canvases.create(DEFAULT_CANVAS, 100, 100).await?;
+        }
+        Ok(canvases)
+    }
+
+    fn dir(&self, name: &str) -> PathBuf {
+        self.config.data_dir.join("canvases").join(name)
+    }
+
+    /// Creates and starts a blank canvas, returns `false` if the name is taken.
+    pub async fn create(&self, name: &str, width: u32, height: u32) -> io::Result<bool> {
+        {
+            let mut creating = self.creating.lock().unwrap();
+            if self.entries.read().unwrap().contains_key(name) || !creating.insert(name.into()) {
+                return Ok(false);
+            }
+        }
+        // The name stays taken until the canvas is in `entries`, or its creation failed.
+        let created = self.create_reserved(name, width, height).await;
+        self.creating.lock().unwrap().remove(name);
+        created.map(|()| true)
+    }
+
+    async fn create_reserved(&self, name: &str, width: u32, height: u32) -> io::Result<()> {
+        let settings = CanvasSettings {
+            width,
+            height,
+            palette: Vec::new(),
+            schedule: Schedule::default(),
+            layers: Vec::new(),
+            cooldown_ms: None,
+        };
+        let dir = self.dir(name);
+        fs::create_dir_all(&dir)?;
+        settings.save(&dir.join("canvas.json"))?;
+        self.start(name.to_string(), settings).await
+    }
+
+    async fn start(&self, name: String, settings: CanvasSettings) -> io::Result<()> {
+        let dir = self.dir(&name);
+        let protected_regions = ProtectedRegions::load(dir.join("regions.json"))?;
+        let templates = templates::load(&dir.join("templates.json"))?;
+
+        let now = Instant::now();
+        let mut state = State::new();
+        state
+            .set_grid_size(settings.width as usize, settings.height as usize)
+            .await;
+        state.set_palette(settings.palette);
+        state.set_schedule(settings.schedule);
+        state.set_layers(settings.layers);
+        state.set_templates(templates).await;
+        let timer = self.metrics.snapshot_serialization_seconds.start_timer();
+        state.set_new_encoded_grid_data().await;
+        timer.observe_duration();
+        info!(
+            canvas = %name,
+            width = settings.width,
+            height = settings.height,
+            elapsed = ?now.elapsed(),
+            "grid ready"
+        );
+        let state = Arc::new(RwLock::new(state));
+
+        let (sender_grid_manipulator, receiver_grid_manipulator) = mpsc::channel::<GridCommand>(16);
+        let (sender_clients, _receiver_clients) = broadcast::channel::<Vec<u8>>(16);
+        let cooldown = settings.cooldown_ms.map(Duration::from_millis);
+        let grid_manipulator = GridManipulator {
+            state: state.clone(),
+            cooldowns: Cooldowns::new(cooldown.unwrap_or(self.config.cooldown)),
+            cooldown,
+            default_cooldown: self.config.cooldown,
+            bans: self.bans.clone(),
+            protected_regions,
+            history: History::new(self.config.history_limit),
+            undo_window: self.config.undo_window,
+            sender_clients: sender_clients.clone(),
+            metrics: self.metrics.clone(),
+            queue_depth: self
+                .metrics
+                .manipulator_queue_depth
+                .with_label_values(&[&name]),
+            settings_path: dir.join("canvas.json"),
+            templates_path: dir.join("templates.json"),
+        };
+        tokio::spawn(
+            grid_manipulator
+                .run(receiver_grid_manipulator)
+                .instrument(info_span!("grid_manipulator", canvas = %name)),
+        );
+        tokio::spawn(
+            full_grid_saver(
+                state.clone(),
+                self.metrics.clone(),
+                sender_grid_manipulator.clone(),
+            )
+            .instrument(info_span!("full_grid_saver", canvas = %name)),
+        );
+
+        let canvas = Canvas {
+            name: name.clone(),
+            state,
+            sender_grid_manipulator,
+        };
+        self.entries.write().unwrap().insert(
+            name,
+            Entry {
+                canvas,
+                sender_clients,
+            },
+        );
+        Ok(())
+    }
+
+    /// Stops the canvas, disconnecting its clients, and deletes its files. Returns `false`
+    /// if there was no such canvas.
+    pub async fn delete(&self, name: &str) -> io::Result<bool> {
+        let entry = match self.entries.write().unwrap().remove(name) {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+        drop(entry.sender_clients);
+        // Commands still queued are dropped, their senders hear the manipulator stopped.
+        let _ = entry
+            .canvas
+            .sender_grid_manipulator
+            .send(GridCommand::Stop)
+            .await;
+        let _ = self
+            .metrics
+            .manipulator_queue_depth
+            .remove_label_values(&[name]);
+        fs::remove_dir_all(self.dir(name))?;
+        Ok(true)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Canvas> {
+        let entries = self.entries.read().unwrap();
+        entries.get(name).map(|entry| entry.canvas.clone())
+    }
+
+    /// The canvas with a subscription to the updates of its grid.
+    pub fn join(&self, name: &str) -> Option<(Canvas, broadcast::Receiver<Vec<u8>>)> {
+        let entries = self.entries.read().unwrap();
+        let entry = entries.get(name)?;
+        Some((entry.canvas.clone(), entry.sender_clients.subscribe()))
+    }
+
+    /// Sends the message to the clients of every canvas, returns how many it reached.
+    pub fn broadcast(&self, encoded_data: Vec<u8>) -> usize {
+        let entries = self.entries.read().unwrap();
+        entries
+            .values()
+            .map(|entry| {
+                entry
+                    .sender_clients
+                    .send(encoded_data.clone())
+                    .unwrap_or_default()
+            })
+            .sum()
+    }
+
+    /// Every canvas, sorted by name.
+    pub fn list(&self) -> Vec<Canvas> {
+        let entries = self.entries.read().unwrap();
+        let mut canvases: Vec<Canvas> =
+            entries.values().map(|entry| entry.canvas.clone()).collect();
+        canvases.sort_by(|a, b| a.name.cmp(&b.name));
+        canvases
+    }
+}
+
+/// Refreshes the snapshot sent to new clients until the canvas's grid manipulator stops.
+async fn full_grid_saver(
+    state: Arc<RwLock<State>>,
+    metrics: Arc<Metrics>,
+    sender_grid_manipulator: mpsc::Sender<GridCommand>,
+) {
+    let mut tick = tokio::time::interval(Duration::from_millis(5000));
+    loop {
+        tokio::select! {
+            _ = tick.tick() => {}
+            () = sender_grid_manipulator.closed() => break,
+        }
+        let timer = metrics.snapshot_serialization_seconds.start_timer();
+        state.read().await.set_new_encoded_grid_data().await;
+        debug!(elapsed = timer.stop_and_record(), "grid snapshot refreshed");
+    }
+}
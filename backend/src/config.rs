@@ -0,0 +1,119 @@
+use std::{env, path::PathBuf, time::Duration};
+
+use local_ip_address::local_ip;
+
+/// Server settings, read once at startup from the command line and `PLACE_*` variables.
+pub struct Config {
+    /// Address the WebSocket server listens on, `--host` binds the machine's LAN address.
+    pub addr: String,
+    /// Address serving Prometheus metrics on `/metrics` (`PLACE_METRICS_ADDR`).
+    pub metrics_addr: String,
+    /// How long a placement waits for room in the grid manipulator queue before the
+    /// client is told the server is busy (`PLACE_PLACEMENT_TIMEOUT_MS`).
+    pub placement_timeout: Duration,
+    /// Key signing session tokens (`PLACE_SESSION_SECRET`), a random one is generated when
+    /// unset and tokens stop being valid on restart.
+    pub session_secret: Option<String>,
+    /// Most connections held at once (`PLACE_MAX_CONNECTIONS`), zero lifts the cap. Each one
+    /// takes a file descriptor, so it has to stay under the process's limit (`ulimit -n`).
+    pub max_connections: usize,
+    /// Most connections held at once from a single address (`PLACE_MAX_CONNECTIONS_PER_IP`),
+    /// zero lifts the cap.
+    pub max_connections_per_ip: usize,
+    /// Largest message a client may send, in bytes (`PLACE_MAX_MESSAGE_BYTES`).
+    pub max_message_size: usize,
+    /// Largest frame a client may send, in bytes (`PLACE_MAX_FRAME_BYTES`), messages above
+    /// it have to be fragmented.
+    pub max_frame_size: usize,
+    /// Most frames a client may send within a second, control frames included
+    /// (`PLACE_MAX_FRAMES_PER_SECOND`), zero lifts the limit.
+    pub max_frames_per_second: u32,
+    /// How often clients are pinged to check they're still there (`PLACE_PING_INTERVAL_MS`),
+    /// zero disables pings.
+    pub ping_interval: Duration,
+    /// How long a client may stay silent, not even answering pings, before its connection is
+    /// closed (`PLACE_IDLE_TIMEOUT_MS`), zero keeps silent clients connected.
+    pub idle_timeout: Duration,
+    /// Minimum time between two placements of the same session (`PLACE_COOLDOWN_MS`),
+    /// zero disables the cooldown.
+    pub cooldown: Duration,
+    /// How long players can undo their last placement (`PLACE_UNDO_SECONDS`), zero disables
+    /// undoing.
+    pub undo_window: Duration,
+    /// How many placements are remembered for rollbacks (`PLACE_HISTORY_LIMIT`).
+    pub history_limit: usize,
+    /// Directory holding the data kept across restarts, like the ban list (`PLACE_DATA_DIR`).
+    pub data_dir: PathBuf,
+    /// JSON Web Key Set used to verify bearer tokens (`PLACE_JWKS_PATH`). When set, only
+    /// authenticated clients can place pixels and everyone else gets a read-only view.
+    pub jwks_path: Option<String>,
+    /// Required `iss` claim of bearer tokens (`PLACE_JWT_ISSUER`).
+    pub jwt_issuer: Option<String>,
+    /// Required `aud` claim of bearer tokens (`PLACE_JWT_AUDIENCE`).
+    pub jwt_audience: Option<String>,
+    /// Unix time in seconds before which placements are refused (`PLACE_STARTS_AT`), only
+    /// applied to a default canvas without a saved schedule.
+    pub starts_at: Option<u64>,
+    /// Unix time in seconds from which placements are refused (`PLACE_ENDS_AT`), like
+    /// `starts_at`.
+    pub ends_at: Option<u64>,
+    /// Bearer token of the admin channel on `/admin` (`PLACE_ADMIN_TOKEN`), which is
+    /// disabled when unset.
+    pub admin_token: Option<String>,
+    /// Role of the bearer tokens allowed to paint batches of pixels (`PLACE_BULK_ROLE`), e.g.
+    /// for event bots. Nobody can when unset.
+    pub bulk_role: Option<String>,
+    /// Whether placements are broadcast with the public id of their placer
+    /// (`PLACE_SHARE_PLACERS`), they're anonymous otherwise.
+    pub share_placers: bool,
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let addr = match env::args().nth(1) {
+            Some(arg) if arg == "--host" => local_ip().unwrap().to_string() + ":8080",
+            Some(_) | None => "127.0.0.1:8080".to_string(),
+        };
+
+        Config {
+            addr,
+            metrics_addr: env_or("PLACE_METRICS_ADDR", "127.0.0.1:9090".to_string()),
+            placement_timeout: Duration::from_millis(env_or("PLACE_PLACEMENT_TIMEOUT_MS", 250)),
+            session_secret: env::var("PLACE_SESSION_SECRET").ok(),
+            max_connections: env_or("PLACE_MAX_CONNECTIONS", 1_000),
+            max_connections_per_ip: env_or("PLACE_MAX_CONNECTIONS_PER_IP", 50),
+            max_message_size: env_or("PLACE_MAX_MESSAGE_BYTES", 4 << 20),
+            max_frame_size: env_or("PLACE_MAX_FRAME_BYTES", 1 << 20),
+            max_frames_per_second: env_or("PLACE_MAX_FRAMES_PER_SECOND", 50),
+            ping_interval: Duration::from_millis(env_or("PLACE_PING_INTERVAL_MS", 20_000)),
+            idle_timeout: Duration::from_millis(env_or("PLACE_IDLE_TIMEOUT_MS", 60_000)),
+            cooldown: Duration::from_millis(env_or("PLACE_COOLDOWN_MS", 0)),
+            undo_window: Duration::from_secs(env_or("PLACE_UNDO_SECONDS", 10)),
+            history_limit: env_or("PLACE_HISTORY_LIMIT", 100_000),
+            data_dir: env_or("PLACE_DATA_DIR", PathBuf::from("data")),
+            jwks_path: env::var("PLACE_JWKS_PATH").ok(),
+            jwt_issuer: env::var("PLACE_JWT_ISSUER").ok(),
+            jwt_audience: env::var("PLACE_JWT_AUDIENCE").ok(),
+            starts_at: env_opt("PLACE_STARTS_AT"),
+            ends_at: env_opt("PLACE_ENDS_AT"),
+            admin_token: env::var("PLACE_ADMIN_TOKEN").ok(),
+            bulk_role: env::var("PLACE_BULK_ROLE").ok(),
+            share_placers: env_or("PLACE_SHARE_PLACERS", false),
+        }
+    }
+}
+
+/// Parses the variable when it's set, falling back to `default` otherwise.
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env_opt(name).unwrap_or(default)
+}
+
+/// Parses the variable when it's set.
+fn env_opt<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = env::var(name).ok()?;
+    Some(
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{} has an invalid value: {:?}", name, value)),
+    )
+}
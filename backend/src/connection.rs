@@ -0,0 +1,1191 @@
+use std::{borrow::Cow, error, fmt, io, net::IpAddr, sync::Arc, time::Duration};
+
+use bebop::Record;
+use futures_util::{future, pin_mut, Sink, SinkExt, StreamExt};
+use sha2::{Digest, Sha256};
+use tokio::{
+    net::TcpStream,
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc, oneshot, RwLock,
+    },
+    time,
+};
+use tokio_tungstenite::{
+    tungstenite::{
+        self,
+        error::ProtocolError,
+        handshake::server::{Callback, ErrorResponse, Request, Response},
+        http::{header, StatusCode, Uri},
+        protocol::{frame::coding::CloseCode, CloseFrame, WebSocketConfig},
+        Message,
+    },
+    WebSocketStream,
+};
+use tracing::{debug, field, info, info_span, warn, Instrument, Span};
+
+use crate::{
+    admin,
+    auth::{Identity, Verifier},
+    canvas::{is_valid_name, Canvas, Canvases, DEFAULT_CANVAS},
+    config::Config,
+    debug_protocol::{DebugReply, DebugRequest},
+    generated::grid::*,
+    limits::FrameRate,
+    metrics::{GaugeGuard, Metrics},
+    moderation::{unix_now, BanList, Sanction},
+    place::{self, Batch, BatchPlacement, GridCommand, Placement, PlacementStatus, Region, Undo},
+    protocol,
+    session::{SessionId, SessionKeys},
+    templates::{Run, Template},
+};
+
+/// Everything that can end a client connection early.
+#[derive(Debug)]
+pub enum ConnectionError {
+    /// The socket was accepted but its peer address couldn't be read.
+    PeerAddr(io::Error),
+    /// The client didn't complete the WebSocket handshake.
+    Handshake(tungstenite::Error),
+    /// Writing a frame to the client failed.
+    Send(tungstenite::Error),
+    /// Reading a frame from the client failed, or the frame was too large.
+    Receive(tungstenite::Error),
+    /// The client sent more frames per second than allowed.
+    Flooding,
+}
+
+impl ConnectionError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConnectionError::PeerAddr(_) => "peer_addr",
+            ConnectionError::Handshake(_) => "handshake",
+            ConnectionError::Send(_) => "send",
+            ConnectionError::Receive(tungstenite::Error::Capacity(_)) => "too_large",
+            ConnectionError::Receive(_) => "receive",
+            ConnectionError::Flooding => "flooding",
+        }
+    }
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionError::PeerAddr(err) => write!(f, "could not read peer address: {}", err),
+            ConnectionError::Handshake(err) => write!(f, "websocket handshake failed: {}", err),
+            ConnectionError::Send(err) => write!(f, "could not send to client: {}", err),
+            ConnectionError::Receive(err) => write!(f, "could not receive from client: {}", err),
+            ConnectionError::Flooding => write!(f, "client sent too many frames per second"),
+        }
+    }
+}
+
+impl error::Error for ConnectionError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ConnectionError::PeerAddr(err) => Some(err),
+            ConnectionError::Handshake(err)
+            | ConnectionError::Send(err)
+            | ConnectionError::Receive(err) => Some(err),
+            ConnectionError::Flooding => None,
+        }
+    }
+}
+
+/// How long a client has to announce its protocol versions before it's taken for one that
+/// predates the handshake.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long a misbehaving client has to take its close frame before it's simply dropped.
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Handles shared by every connection task.
+#[derive(Clone)]
+pub struct Context {
+    pub config: Arc<Config>,
+    pub canvases: Arc<Canvases>,
+    pub metrics: Arc<Metrics>,
+    pub session_keys: Arc<SessionKeys>,
+    /// Checks bearer tokens, `None` when authentication is disabled and everyone can place.
+    pub verifier: Option<Arc<dyn Verifier>>,
+    /// Also read by the handshake, hence the blocking lock.
+    pub bans: Arc<std::sync::RwLock<BanList>>,
+}
+
+/// What a client is allowed to do over its connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Access {
+    ReadWrite,
+    /// May also paint batches of pixels, its bearer token has the bulk placement role.
+    Privileged,
+    /// Asked to only watch, receives the grid and its updates but never reaches the grid
+    /// manipulator.
+    Spectator,
+    /// Watches like a spectator because placing pixels takes authentication.
+    Unauthenticated,
+    /// Connected to `/admin` with the admin token, sends commands instead of placements.
+    Admin,
+}
+
+/// Serves one client until it disconnects, logging how the connection ended.
+pub async fn handle_connection(stream: TcpStream, id: u64, context: Context) {
+    let span = info_span!(
+        "connection",
+        id,
+        peer = field::Empty,
+        canvas = field::Empty,
+        session = field::Empty,
+        user = field::Empty,
+        access = field::Empty,
+        protocol = field::Empty
+    );
+    let result = accept_connection(stream, &context)
+        .instrument(span.clone())
+        .await;
+    let _entered = span.enter();
+    match result {
+        Ok(()) => info!("client disconnected"),
+        Err(error) => {
+            let counter = context
+                .metrics
+                .connection_errors
+                .with_label_values(&[error.kind()]);
+            counter.inc();
+            warn!(
+                kind = error.kind(),
+                count = counter.get(),
+                "connection closed: {}",
+                error
+            );
+        }
+    }
+}
+
+async fn accept_connection(stream: TcpStream, context: &Context) -> Result<(), ConnectionError> {
+    let metrics = &context.metrics;
+    let addr = stream.peer_addr().map_err(ConnectionError::PeerAddr)?;
+    Span::current().record("peer", field::display(addr));
+
+    let mut upgrade_request = UpgradeRequest::default();
+    let callback = UpgradeCallback {
+        request: &mut upgrade_request,
+        verifier: context.verifier.as_deref(),
+        admin_token: context.config.admin_token.as_deref(),
+        canvases: &context.canvases,
+    };
+    let ws_config = WebSocketConfig {
+        max_message_size: Some(context.config.max_message_size),
+        max_frame_size: Some(context.config.max_frame_size),
+        ..WebSocketConfig::default()
+    };
+    let mut ws_stream =
+        tokio_tungstenite::accept_hdr_async_with_config(stream, callback, Some(ws_config))
+            .await
+            .map_err(ConnectionError::Handshake)?;
+    if upgrade_request.admin {
+        Span::current().record("access", field::debug(Access::Admin));
+        info!("admin connected");
+        return serve_admin(ws_stream, context).await;
+    }
+    let (canvas, mut receiver_clients) = match upgrade_request.canvas.take() {
+        Some(joined) => joined,
+        // The handshake only succeeds for admins and clients of an existing canvas.
+        None => return Ok(()),
+    };
+    Span::current().record("canvas", canvas.name.as_str());
+
+    let session = match &upgrade_request.identity {
+        // Authenticated users keep their session, and cooldown, across devices.
+        Some(identity) => {
+            Span::current().record("user", identity.subject.as_str());
+            context.session_keys.for_subject(&identity.subject)
+        }
+        // Clients without a valid token, new or tampered with, start a fresh session.
+        None => upgrade_request
+            .session_token
+            .and_then(|token| context.session_keys.verify(&token))
+            .unwrap_or_else(SessionId::random),
+    };
+    let identity = upgrade_request.identity.map(Arc::new);
+    let unauthenticated = context.verifier.is_some() && identity.is_none();
+    let privileged = context.config.bulk_role.as_ref().is_some_and(|role| {
+        identity
+            .as_ref()
+            .is_some_and(|identity| identity.roles.contains(role))
+    });
+    let access = if upgrade_request.spectator {
+        Access::Spectator
+    } else if unauthenticated {
+        Access::Unauthenticated
+    } else if privileged {
+        Access::Privileged
+    } else {
+        Access::ReadWrite
+    };
+    Span::current().record("session", field::display(session));
+    Span::current().record("access", field::debug(access));
+
+    // Muted clients may still watch, their placements are refused by the grid manipulator.
+    let ban = context
+        .bans
+        .read()
+        .unwrap()
+        .lookup(session, addr.ip())
+        .filter(|ban| ban.sanction == Sanction::Ban)
+        .cloned();
+    if let Some(ban) = ban {
+        info!(%ban, "refusing banned client");
+        metrics
+            .refused_connections
+            .with_label_values(&["banned"])
+            .inc();
+        let close_frame = CloseFrame {
+            code: CloseCode::Policy,
+            reason: Cow::Owned(close_reason(ban.notice())),
+        };
+        return match ws_stream.close(Some(close_frame)).await {
+            Err(err) if !is_disconnect(&err) => Err(ConnectionError::Send(err)),
+            _ => Ok(()),
+        };
+    }
+    // The first message of a client that predates the handshake, handled once it's served.
+    let mut first_message = None;
+    // The newest version both speak, `None` for clients that predate the handshake, which
+    // don't check the versions they're sent.
+    let negotiated = match read_hello(&mut ws_stream)
+        .await
+        .map_err(ConnectionError::Receive)?
+    {
+        Hello::Versions(versions) => match protocol::pick_version(versions.iter().copied()) {
+            Some(version) => Some(version),
+            None => {
+                info!(
+                    ?versions,
+                    "refusing client speaking no protocol version in common"
+                );
+                metrics
+                    .refused_connections
+                    .with_label_values(&["incompatible_protocol"])
+                    .inc();
+                let close_frame = CloseFrame {
+                    code: CloseCode::Protocol,
+                    reason: Cow::Owned(close_reason(format!(
+                        "The server speaks protocol versions {} to {}, please update this client.",
+                        MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+                    ))),
+                };
+                return match ws_stream.close(Some(close_frame)).await {
+                    Err(err) if !is_disconnect(&err) => Err(ConnectionError::Send(err)),
+                    _ => Ok(()),
+                };
+            }
+        },
+        Hello::Other(msg) => {
+            first_message = Some(msg);
+            None
+        }
+        Hello::Nothing => None,
+    };
+    let version = negotiated.unwrap_or(MIN_PROTOCOL_VERSION);
+    Span::current().record("protocol", version);
+    info!("websocket handshake complete");
+    let _connected = GaugeGuard::new(&metrics.connected_clients);
+    let _spectating = matches!(access, Access::Spectator | Access::Unauthenticated)
+        .then(|| GaugeGuard::new(&metrics.spectators));
+
+    let (mut write, mut read) = ws_stream.split();
+    if negotiated.is_some() {
+        let hello = ServerHello { version };
+        write
+            .feed(Message::Binary(protocol::encode_for(
+                version,
+                SERVER_HELLO_OPCODE,
+                &hello,
+            )))
+            .await
+            .map_err(ConnectionError::Send)?;
+    }
+    let session_message = SessionToken {
+        token: &context.session_keys.token(session),
+    };
+    write
+        .feed(Message::Binary(protocol::encode_for(
+            version,
+            SESSION_TOKEN_OPCODE,
+            &session_message,
+        )))
+        .await
+        .map_err(ConnectionError::Send)?;
+    send_snapshot(&mut write, &canvas.state, version, metrics)
+        .await
+        .map_err(ConnectionError::Send)?;
+
+    // Replies meant for this client only, interleaved with the broadcast by the send half.
+    let (sender_outbox, mut receiver_outbox) = mpsc::channel::<Message>(16);
+    // When the client last sent anything, pongs included.
+    let last_seen = std::sync::Mutex::new(time::Instant::now());
+    let mut frame_rate = FrameRate::new(context.config.max_frames_per_second);
+    let public_id = context.session_keys.public_id(session);
+
+    let receive_future = async {
+        loop {
+            let msg = match first_message.take() {
+                Some(msg) => msg,
+                None => match read.next().await {
+                    Some(msg) => msg.map_err(ConnectionError::Receive)?,
+                    None => break,
+                },
+            };
+            *last_seen.lock().unwrap() = time::Instant::now();
+            if !frame_rate.allow() {
+                return Err(ConnectionError::Flooding);
+            }
+            // Whether the placement came over the JSON debug protocol and is answered in JSON.
+            let (request_id, placement, from_debug) = match msg {
+                Message::Binary(data) => {
+                    debug!(bytes = data.len(), "message received");
+                    match decode_message(&data, negotiated) {
+                        Some(ClientMessage::Placement {
+                            request_id,
+                            placement,
+                        }) => (request_id, placement, false),
+                        Some(ClientMessage::TemplateRequest(name)) => {
+                            let state_guard = canvas.state.read().await;
+                            let encoded_template =
+                                state_guard.find_template(&name).map(Template::encode);
+                            drop(state_guard);
+                            match encoded_template {
+                                Some(encoded_template) => {
+                                    let message = Message::Binary(encoded_template);
+                                    if sender_outbox.send(message).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                None => debug!(template = %name, "no such template"),
+                            }
+                            continue;
+                        }
+                        None => {
+                            metrics
+                                .rejected_placements
+                                .with_label_values(&["malformed"])
+                                .inc();
+                            continue;
+                        }
+                    }
+                }
+                Message::Text(text) => {
+                    debug!(bytes = text.len(), "debug request received");
+                    match answer_debug_request(&text, &canvas, version, access, &public_id).await {
+                        Ok((request_id, placement)) => (request_id, placement, true),
+                        Err(reply) => {
+                            if sender_outbox.send(reply.encode()).await.is_err() {
+                                break;
+                            }
+                            continue;
+                        }
+                    }
+                }
+                // tungstenite answers pings on its own, these only count as activity.
+                Message::Ping(_) | Message::Pong(_) => continue,
+                Message::Close(close_frame) => {
+                    debug!(?close_frame, "client is closing the connection");
+                    // tungstenite queued the close frame answering it, reading on sends it
+                    // and then ends the stream.
+                    continue;
+                }
+                // Only ever built to write raw frames, reading never yields one.
+                Message::Frame(_) => continue,
+            };
+            let refused = match (access, &placement) {
+                (Access::Privileged, _)
+                | (Access::ReadWrite, ClientPlacement::Pixel { .. } | ClientPlacement::Undo) => {
+                    None
+                }
+                // Not counted as unauthorized, spectators chose not to place pixels.
+                (Access::Spectator, _) => Some((PlacementStatus::Spectating, "spectator")),
+                _ => Some((PlacementStatus::Unauthorized, "unauthorized")),
+            };
+            let status = match refused {
+                None => {
+                    submit_placement(
+                        context,
+                        &canvas,
+                        placement,
+                        session,
+                        identity.clone(),
+                        addr.ip(),
+                    )
+                    .await
+                }
+                Some((status, reason)) => {
+                    metrics
+                        .rejected_placements
+                        .with_label_values(&[reason])
+                        .inc();
+                    status
+                }
+            };
+            let notice = placement_notice(status);
+            if from_debug {
+                let reply = DebugReply::PlacementResult {
+                    request_id,
+                    status: status.code(),
+                    notice: notice.map(|(_, _, text)| text),
+                };
+                if sender_outbox.send(reply.encode()).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+            let result = PlacementResult {
+                request_id,
+                status: status.code(),
+            };
+            let message = Message::Binary(protocol::encode(PLACEMENT_RESULT_OPCODE, &result));
+            if sender_outbox.send(message).await.is_err() {
+                break;
+            }
+            if let Some((code, severity, text)) = notice {
+                let notice = protocol::encode_notice(code, severity, &text);
+                if sender_outbox.send(Message::Binary(notice)).await.is_err() {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    };
+
+    let ping_interval = context.config.ping_interval;
+    // The period only has to be valid when pings are disabled, the interval never ticks then.
+    let ping_period = if ping_interval.is_zero() {
+        Duration::from_secs(1)
+    } else {
+        ping_interval
+    };
+    let mut ping_tick = time::interval_at(time::Instant::now() + ping_period, ping_period);
+
+    let send_future = async {
+        loop {
+            let message = tokio::select! {
+                _ = ping_tick.tick(), if !ping_interval.is_zero() => {
+                    write.send(Message::Ping(Vec::new())).await?;
+                    continue;
+                }
+                received = receiver_clients.recv() => match received {
+                    Ok(encoded_data) => Message::Binary(encoded_data),
+                    Err(RecvError::Lagged(skipped)) => {
+                        metrics.broadcast_lag_events.inc();
+                        // The missed updates are gone, catch the client up with a fresh snapshot.
+                        warn!(
+                            skipped,
+                            "client lagged behind the broadcast, resending the grid"
+                        );
+                        send_snapshot(&mut write, &canvas.state, version, metrics).await?;
+                        continue;
+                    }
+                    // The canvas was deleted and its grid manipulator stopped.
+                    Err(RecvError::Closed) => {
+                        let close_frame = CloseFrame {
+                            code: CloseCode::Away,
+                            reason: Cow::Borrowed("This canvas was deleted."),
+                        };
+                        write.send(Message::Close(Some(close_frame))).await?;
+                        return Ok::<(), tungstenite::Error>(());
+                    }
+                },
+                Some(message) = receiver_outbox.recv() => message,
+            };
+            let message = match message {
+                Message::Binary(encoded_data) => match protocol::downgrade(encoded_data, version) {
+                    Some(encoded_data) => Message::Binary(encoded_data),
+                    None => continue,
+                },
+                message => message,
+            };
+            metrics.bytes_sent.inc_by(message.len() as u64);
+            write.send(message).await?;
+        }
+    };
+
+    // Runs apart from the send half, whose writes never finish once a dead connection's
+    // buffers are full.
+    let idle_timeout = context.config.idle_timeout;
+    let idle_future = async {
+        if idle_timeout.is_zero() {
+            return future::pending().await;
+        }
+        loop {
+            let deadline = *last_seen.lock().unwrap() + idle_timeout;
+            if time::Instant::now() >= deadline {
+                break;
+            }
+            time::sleep_until(deadline).await;
+        }
+    };
+
+    // Scoped so that the write half is free again to close the connection of an abuser.
+    let result = {
+        pin_mut!(receive_future, send_future, idle_future);
+        tokio::select! {
+            result = &mut receive_future => match result {
+                Err(ConnectionError::Receive(err)) if is_disconnect(&err) => Ok(()),
+                result => result,
+            },
+            result = &mut send_future => match result {
+                Err(err) if !is_disconnect(&err) => Err(ConnectionError::Send(err)),
+                _ => Ok(()),
+            },
+            () = &mut idle_future => {
+                info!(?idle_timeout, "dropping silent client");
+                metrics.idle_timeouts.inc();
+                Ok(())
+            }
+        }
+    };
+    let close_frame = match &result {
+        Err(ConnectionError::Flooding) => Some(CloseFrame {
+            code: CloseCode::Policy,
+            reason: Cow::Borrowed("Too many messages, slow down."),
+        }),
+        Err(ConnectionError::Receive(tungstenite::Error::Capacity(_))) => Some(CloseFrame {
+            code: CloseCode::Size,
+            reason: Cow::Borrowed("This message is too large."),
+        }),
+        _ => None,
+    };
+    if let Some(close_frame) = close_frame {
+        // The client may have stopped reading, don't wait on it for long.
+        let _ = time::timeout(CLOSE_TIMEOUT, write.send(Message::Close(Some(close_frame)))).await;
+    }
+    result
+}
+
+/// What a client asked to paint.
+enum ClientPlacement {
+    Pixel {
+        layer: u32,
+        pixel: Pixel,
+    },
+    /// Only accepted from privileged clients.
+    Batch {
+        layer: u32,
+        batch: Batch,
+    },
+    /// Takes back the client's most recent placement.
+    Undo,
+}
+
+/// A message sent by a client.
+enum ClientMessage {
+    Placement {
+        request_id: u32,
+        placement: ClientPlacement,
+    },
+    /// Asks for the image of the named template.
+    TemplateRequest(String),
+}
+
+/// Extracts what the client asked for from its message, `None` for anything else.
+///
+/// Bare `Pixel` messages from clients that don't track their placements get request id 0,
+/// placements from clients that don't know about layers target the grid, layer 0. Once a
+/// version was negotiated, messages stamped with another are dropped.
+fn decode_message(buf: &[u8], negotiated: Option<u32>) -> Option<ClientMessage> {
+    let bebop_data = match protocol::decode(buf) {
+        Ok(bebop_data) => bebop_data,
+        Err(err) => {
+            warn!(error = ?err, "dropping malformed message");
+            return None;
+        }
+    };
+    if negotiated.is_some_and(|version| version != bebop_data.protocol_version) {
+        warn!(
+            version = bebop_data.protocol_version,
+            "dropping message stamped with another protocol version"
+        );
+        return None;
+    }
+    let data = &bebop_data.encoded_data;
+    let placement = match bebop_data.opcode {
+        LAYER_PIXEL_PLACEMENT_OPCODE => LayerPixelPlacement::deserialize(data).map(|placement| {
+            let LayerPixelPlacement {
+                request_id,
+                layer,
+                pixel,
+            } = placement;
+            (request_id, ClientPlacement::Pixel { layer, pixel })
+        }),
+        PIXEL_PLACEMENT_OPCODE => PixelPlacement::deserialize(data).map(|placement| {
+            let PixelPlacement { request_id, pixel } = placement;
+            (request_id, ClientPlacement::Pixel { layer: 0, pixel })
+        }),
+        PIXEL_OPCODE => {
+            Pixel::deserialize(data).map(|pixel| (0, ClientPlacement::Pixel { layer: 0, pixel }))
+        }
+        RECT_FILL_OPCODE => RectFill::deserialize(data).map(|fill| {
+            let region = Region {
+                x: fill.x,
+                y: fill.y,
+                width: fill.width,
+                height: fill.height,
+            };
+            let batch = Batch::Rect {
+                region,
+                color: fill.color,
+            };
+            let layer = fill.layer;
+            (fill.request_id, ClientPlacement::Batch { layer, batch })
+        }),
+        PIXEL_LIST_OPCODE => PixelList::deserialize(data).map(|list| {
+            let batch = Batch::Pixels(list.pixels.iter().collect());
+            let layer = list.layer;
+            (list.request_id, ClientPlacement::Batch { layer, batch })
+        }),
+        BITMAP_OPCODE => Bitmap::deserialize(data).map(|bitmap| {
+            let batch = Batch::Bitmap {
+                x: bitmap.x,
+                y: bitmap.y,
+                width: bitmap.width,
+                runs: bitmap.runs.iter().collect(),
+            };
+            let layer = bitmap.layer;
+            (bitmap.request_id, ClientPlacement::Batch { layer, batch })
+        }),
+        UNDO_PLACEMENT_OPCODE => {
+            UndoPlacement::deserialize(data).map(|undo| (undo.request_id, ClientPlacement::Undo))
+        }
+        TEMPLATE_REQUEST_OPCODE => {
+            return match TemplateRequest::deserialize(data) {
+                Ok(request) => Some(ClientMessage::TemplateRequest(request.name.to_string())),
+                Err(err) => {
+                    warn!(error = ?err, "dropping malformed template request");
+                    None
+                }
+            };
+        }
+        opcode => {
+            debug!(opcode, "ignoring unexpected opcode");
+            return None;
+        }
+    };
+    match placement {
+        Ok((request_id, placement)) => Some(ClientMessage::Placement {
+            request_id,
+            placement,
+        }),
+        Err(err) => {
+            warn!(error = ?err, "dropping malformed placement");
+            None
+        }
+    }
+}
+
+/// Tells the client in words why its placement was refused, as the code, severity and text
+/// of a notice, `None` when it was accepted.
+fn placement_notice(status: PlacementStatus) -> Option<(u32, u32, String)> {
+    let (severity, text) = match status {
+        PlacementStatus::Accepted => return None,
+        PlacementStatus::RateLimited { remaining } => {
+            let text = format!(
+                "You can place your next pixel in {} seconds.",
+                remaining.as_secs_f64().ceil()
+            );
+            return Some((NOTICE_COOLDOWN, NOTICE_INFO, text));
+        }
+        PlacementStatus::ServerBusy => (
+            NOTICE_WARNING,
+            "The server is busy, please place your pixel again.",
+        ),
+        PlacementStatus::OutOfBounds => (NOTICE_WARNING, "That pixel is outside of the canvas."),
+        PlacementStatus::Banned => (NOTICE_ERROR, "You are not allowed to place pixels."),
+        PlacementStatus::InvalidColor => (NOTICE_WARNING, "That color is not part of the palette."),
+        PlacementStatus::CanvasLocked => (NOTICE_WARNING, "The canvas is locked right now."),
+        PlacementStatus::CanvasEnded => (NOTICE_INFO, "The event is over, the canvas is closed."),
+        PlacementStatus::Protected => (
+            NOTICE_WARNING,
+            "That area is reserved, you cannot paint there.",
+        ),
+        PlacementStatus::LayerRestricted => {
+            (NOTICE_WARNING, "Only moderators can paint this layer.")
+        }
+        PlacementStatus::Unauthorized => (NOTICE_ERROR, "Sign in to place pixels."),
+        PlacementStatus::Spectating => (
+            NOTICE_INFO,
+            "You are only watching, reconnect without spectator mode to place pixels.",
+        ),
+        PlacementStatus::NothingToUndo => (
+            NOTICE_WARNING,
+            "It is too late to undo, or someone painted over your pixel.",
+        ),
+    };
+    Some((NOTICE_PLACEMENT_REJECTED, severity, text.to_string()))
+}
+
+/// Answers a request of the JSON debug protocol, or hands back the placement it asks for so
+/// it goes through the same checks as any other.
+async fn answer_debug_request(
+    text: &str,
+    canvas: &Canvas,
+    version: u32,
+    access: Access,
+    public_id: &str,
+) -> Result<(u32, ClientPlacement), DebugReply> {
+    let request = DebugRequest::parse(text)?;
+    let reply = match request {
+        DebugRequest::Place {
+            request_id,
+            layer,
+            x,
+            y,
+            color,
+        } => {
+            let pixel = Pixel { x, y, color };
+            return Ok((request_id, ClientPlacement::Pixel { layer, pixel }));
+        }
+        DebugRequest::Undo { request_id } => return Ok((request_id, ClientPlacement::Undo)),
+        DebugRequest::Info => {
+            let state_guard = canvas.state.read().await;
+            DebugReply::Info {
+                canvas: canvas.name.clone(),
+                width: state_guard.get_grid_width(),
+                height: state_guard.get_grid_height(),
+                layers: state_guard.get_layers().len(),
+                protocol: version,
+                access: format!("{:?}", access),
+                placer: public_id.to_string(),
+            }
+        }
+        DebugRequest::Pixel { layer, x, y } => {
+            let color = canvas.state.read().await.get_layer_color(layer, x, y).await;
+            DebugReply::Pixel { layer, x, y, color }
+        }
+    };
+    Err(reply)
+}
+
+/// Queues the placement for the grid manipulator and waits for its verdict.
+///
+/// When the queue stays full longer than the configured timeout nothing is painted and
+/// the client is told the server is busy instead.
+async fn submit_placement(
+    context: &Context,
+    canvas: &Canvas,
+    placement: ClientPlacement,
+    session: SessionId,
+    identity: Option<Arc<Identity>>,
+    ip: IpAddr,
+) -> PlacementStatus {
+    let sender = &canvas.sender_grid_manipulator;
+    let (reply, response) = oneshot::channel();
+    let command = match placement {
+        ClientPlacement::Pixel { layer, pixel } => GridCommand::Place(Placement {
+            layer,
+            pixel,
+            session,
+            identity,
+            ip,
+            placer: context
+                .config
+                .share_placers
+                .then(|| context.session_keys.public_id(session).into()),
+            reply,
+        }),
+        ClientPlacement::Batch { layer, batch } => GridCommand::PaintBatch(BatchPlacement {
+            layer,
+            batch,
+            session,
+            identity,
+            ip,
+            reply,
+        }),
+        ClientPlacement::Undo => GridCommand::Undo(Undo { session, ip, reply }),
+    };
+    let sent = sender
+        .send_timeout(command, context.config.placement_timeout)
+        .await;
+    context
+        .metrics
+        .manipulator_queue_depth
+        .with_label_values(&[&canvas.name])
+        .set((sender.max_capacity() - sender.capacity()) as i64);
+    if let Err(err) = sent {
+        warn!(error = %err, "grid manipulator queue is full, rejecting pixel");
+        context
+            .metrics
+            .rejected_placements
+            .with_label_values(&["server_busy"])
+            .inc();
+        return PlacementStatus::ServerBusy;
+    }
+    // The manipulator only drops the reply if it stopped, which a retry won't fix either.
+    response.await.unwrap_or(PlacementStatus::ServerBusy)
+}
+
+/// Runs the commands an admin sends, answering each with an `AdminResult`.
+///
+/// Commands go through the grid manipulator like placements do, so they're applied in
+/// order with them and their effect is broadcast to every client.
+async fn serve_admin(
+    mut ws_stream: WebSocketStream<TcpStream>,
+    context: &Context,
+) -> Result<(), ConnectionError> {
+    while let Some(msg) = ws_stream.next().await {
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(err) if is_disconnect(&err) => break,
+            Err(err) => return Err(ConnectionError::Receive(err)),
+        };
+        let (request_id, command) = match msg {
+            Message::Binary(data) => match decode_admin_message(&data) {
+                Some(decoded) => decoded,
+                None => continue,
+            },
+            Message::Text(text) => {
+                warn!(bytes = text.len(), "refusing admin command sent as text");
+                let message = "admin commands are sent as binary AdminCommand messages";
+                (0, Err(message.to_string()))
+            }
+            // tungstenite answers pings and close frames on its own.
+            Message::Ping(_) | Message::Pong(_) | Message::Close(_) | Message::Frame(_) => continue,
+        };
+        let outcome = match command {
+            Ok(command) => admin::execute(command, context).await,
+            Err(err) => Err(err),
+        };
+        let (success, message) = match &outcome {
+            Ok(message) => (true, message.as_str()),
+            Err(message) => (false, message.as_str()),
+        };
+        let result = AdminResult {
+            request_id,
+            success,
+            message,
+        };
+        let sent = ws_stream
+            .send(Message::Binary(protocol::encode(
+                ADMIN_RESULT_OPCODE,
+                &result,
+            )))
+            .await;
+        match sent {
+            Err(err) if is_disconnect(&err) => break,
+            Err(err) => return Err(ConnectionError::Send(err)),
+            Ok(()) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Extracts the request id and the command from an admin's message, `None` for anything else.
+fn decode_admin_message(buf: &[u8]) -> Option<(u32, Result<admin::AdminCommand, String>)> {
+    let decoded = protocol::decode(buf).and_then(|bebop_data| match bebop_data.opcode {
+        ADMIN_COMMAND_OPCODE => {
+            AdminCommand::deserialize(&bebop_data.encoded_data).map(|command| {
+                info!(command = command.command, "running admin command");
+                Some((command.request_id, command.command.parse()))
+            })
+        }
+        TEMPLATE_UPLOAD_OPCODE => {
+            TemplateUpload::deserialize(&bebop_data.encoded_data).map(|upload| {
+                info!(
+                    canvas = upload.canvas,
+                    template = upload.template.name,
+                    "uploading template"
+                );
+                Some((upload.request_id, template_upload_command(upload)))
+            })
+        }
+        opcode => {
+            debug!(opcode, "ignoring unexpected opcode");
+            Ok(None)
+        }
+    });
+    decoded.unwrap_or_else(|err| {
+        warn!(error = ?err, "dropping malformed admin command");
+        None
+    })
+}
+
+/// The command adding the uploaded template to its canvas.
+fn template_upload_command(upload: TemplateUpload) -> Result<admin::AdminCommand, String> {
+    if !is_valid_name(upload.canvas) {
+        return Err(format!("no canvas is named {}", upload.canvas));
+    }
+    let template = upload.template;
+    let template = Template::new(
+        template.name.to_string(),
+        template.layer,
+        template.x,
+        template.y,
+        template.width,
+        template.runs.iter().map(Run::from).collect(),
+    )?;
+    Ok(admin::AdminCommand::Canvas {
+        canvas: upload.canvas.to_string(),
+        command: admin::CanvasCommand::AddTemplate(template),
+    })
+}
+
+/// Sends the canvas status, the palette, the layers, the templates and the full grid followed
+/// by the changes made since it was encoded, then what's painted on the layers over the grid.
+async fn send_snapshot<S>(
+    write: &mut S,
+    state: &RwLock<place::State>,
+    version: u32,
+    metrics: &Metrics,
+) -> Result<(), S::Error>
+where
+    S: Sink<Message> + Unpin,
+{
+    let state_guard = state.read().await;
+    let encoded_schedule = state_guard.encode_schedule(unix_now());
+    let encoded_palette = state_guard.encode_palette();
+    let encoded_layers = state_guard.encode_layers();
+    let encoded_templates = state_guard.encode_templates();
+    let encoded_grid_data = state_guard.get_encoded_grid_data_cloned().await;
+    let encoded_delta_data = state_guard.get_encoded_delta_data_cloned().await;
+    let encoded_overlays = state_guard.encode_overlays().await;
+    drop(state_guard);
+
+    let messages = [
+        encoded_schedule,
+        encoded_palette,
+        encoded_layers,
+        encoded_templates,
+        encoded_grid_data,
+        encoded_delta_data,
+    ];
+    let messages = messages
+        .into_iter()
+        .chain(encoded_overlays)
+        .filter_map(|encoded_data| protocol::downgrade(encoded_data, version));
+    for encoded_data in messages {
+        metrics.bytes_sent.inc_by(encoded_data.len() as u64);
+        write.feed(Message::Binary(encoded_data)).await?;
+    }
+    write.flush().await
+}
+
+/// What a client sent first, before it was served anything.
+enum Hello {
+    /// The protocol versions it announced in its `ClientHello`.
+    Versions(Vec<u32>),
+    /// Any other message, from a client that predates the handshake or a JSON debug tool,
+    /// left for the receive loop.
+    Other(Message),
+    /// Nothing within [`HELLO_TIMEOUT`], from a client that predates the handshake and waits
+    /// for the grid without saying anything.
+    Nothing,
+}
+
+/// Waits for the client's `ClientHello`, pings and pongs aside.
+async fn read_hello(
+    ws_stream: &mut WebSocketStream<TcpStream>,
+) -> Result<Hello, tungstenite::Error> {
+    let deadline = time::Instant::now() + HELLO_TIMEOUT;
+    loop {
+        let msg = match time::timeout_at(deadline, ws_stream.next()).await {
+            Ok(Some(msg)) => msg?,
+            // A client that left is noticed by the first send.
+            Ok(None) | Err(_) => return Ok(Hello::Nothing),
+        };
+        let data = match &msg {
+            Message::Binary(data) => data,
+            Message::Ping(_) | Message::Pong(_) => continue,
+            // Text frames come from debug tools, which never send a `ClientHello`.
+            _ => return Ok(Hello::Other(msg)),
+        };
+        let versions = protocol::decode(data)
+            .ok()
+            .filter(|bebop_data| bebop_data.opcode == CLIENT_HELLO_OPCODE)
+            .and_then(|bebop_data| ClientHello::deserialize(&bebop_data.encoded_data).ok())
+            .map(|hello| hello.versions.iter().collect());
+        return Ok(match versions {
+            Some(versions) => Hello::Versions(versions),
+            None => Hello::Other(msg),
+        });
+    }
+}
+
+/// What the client asked for in its HTTP upgrade request.
+#[derive(Default)]
+struct UpgradeRequest {
+    /// The canvas picked by the path, already subscribed to its updates.
+    canvas: Option<(Canvas, broadcast::Receiver<Vec<u8>>)>,
+    session_token: Option<String>,
+    /// The client only wants to watch, through a `/spectate` path or `spectator` parameter.
+    spectator: bool,
+    /// Set when the client presented a bearer token the verifier accepted.
+    identity: Option<Identity>,
+    /// The client connected to `/admin` with the admin token.
+    admin: bool,
+}
+
+/// Fills an [`UpgradeRequest`] during the WebSocket handshake.
+///
+/// A bearer token that fails verification ends the handshake with `401 Unauthorized`
+/// rather than silently downgrading the client to read-only. So does a wrong token on
+/// `/admin`, which answers `404 Not Found` when no admin token is configured, like paths
+/// naming no canvas do.
+struct UpgradeCallback<'a> {
+    request: &'a mut UpgradeRequest,
+    verifier: Option<&'a dyn Verifier>,
+    admin_token: Option<&'a str>,
+    canvases: &'a Canvases,
+}
+
+impl Callback for UpgradeCallback<'_> {
+    fn on_request(self, request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+        if request.uri().path() == "/admin" {
+            let admin_token = match self.admin_token {
+                Some(admin_token) => admin_token,
+                None => return Err(error_response(StatusCode::NOT_FOUND, "not found\n")),
+            };
+            // Comparing digests keeps the time taken from revealing how much of it matched.
+            let matches = bearer_token(request).is_some_and(|token| {
+                Sha256::digest(token.as_bytes()) == Sha256::digest(admin_token.as_bytes())
+            });
+            if !matches {
+                warn!("rejecting admin connection with a wrong token");
+                return Err(error_response(
+                    StatusCode::UNAUTHORIZED,
+                    "invalid admin token\n",
+                ));
+            }
+            self.request.admin = true;
+            return Ok(response);
+        }
+
+        let (name, spectate) = match route(request.uri().path()) {
+            Some(route) => route,
+            None => return Err(error_response(StatusCode::NOT_FOUND, "not found\n")),
+        };
+        self.request.canvas = self.canvases.join(name);
+        if self.request.canvas.is_none() {
+            return Err(error_response(StatusCode::NOT_FOUND, "no such canvas\n"));
+        }
+        self.request.session_token = query_param(request.uri(), "session").map(str::to_string);
+        self.request.spectator = spectate || query_param(request.uri(), "spectator").is_some();
+
+        if let (Some(verifier), Some(token)) = (self.verifier, bearer_token(request)) {
+            match verifier.verify(token) {
+                Ok(identity) => self.request.identity = Some(identity),
+                Err(err) => {
+                    warn!(error = %err, "rejecting bearer token");
+                    return Err(error_response(
+                        StatusCode::UNAUTHORIZED,
+                        "invalid bearer token\n",
+                    ));
+                }
+            }
+        }
+        Ok(response)
+    }
+}
+
+/// The canvas a path leads to and whether it's only watched: `/` and `/spectate` for the
+/// default canvas, `/ws/{canvas}` and `/ws/{canvas}/spectate` for any other.
+fn route(path: &str) -> Option<(&str, bool)> {
+    match path {
+        "/" => Some((DEFAULT_CANVAS, false)),
+        "/spectate" => Some((DEFAULT_CANVAS, true)),
+        _ => {
+            let rest = path.strip_prefix("/ws/")?;
+            match rest.split_once('/') {
+                None => Some((rest, false)),
+                Some((name, "")) => Some((name, false)),
+                Some((name, "spectate")) => Some((name, true)),
+                Some(_) => None,
+            }
+        }
+    }
+}
+
+fn error_response(status: StatusCode, body: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(body.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+/// Token from the `Authorization: Bearer` header, or the `access_token` query parameter
+/// for browsers, which can't set headers on WebSocket requests.
+fn bearer_token(request: &Request) -> Option<&str> {
+    request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| query_param(request.uri(), "access_token"))
+}
+
+/// Value of a query string parameter, e.g. `name` in `/?name=value`, empty for a bare `/?name`.
+fn query_param<'a>(uri: &'a Uri, name: &str) -> Option<&'a str> {
+    uri.query()?
+        .split('&')
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Cuts the text to fit the 123 bytes a close frame leaves for its reason.
+fn close_reason(mut reason: String) -> String {
+    if reason.len() > 123 {
+        let mut end = 120;
+        while !reason.is_char_boundary(end) {
+            end -= 1;
+        }
+        reason.truncate(end);
+        reason.push_str("...");
+    }
+    reason
+}
+
+/// Whether the error only means the client went away, which isn't worth reporting.
+fn is_disconnect(err: &tungstenite::Error) -> bool {
+    matches!(
+        err,
+        tungstenite::Error::ConnectionClosed
+            | tungstenite::Error::AlreadyClosed
+            | tungstenite::Error::Protocol(ProtocolError::ResetWithoutClosingHandshake)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_paths_to_canvases() {
+        assert_eq!(route("/"), Some((DEFAULT_CANVAS, false)));
+        assert_eq!(route("/spectate"), Some((DEFAULT_CANVAS, true)));
+        assert_eq!(route("/ws/team-red"), Some(("team-red", false)));
+        assert_eq!(route("/ws/team-red/"), Some(("team-red", false)));
+        assert_eq!(route("/ws/team-red/spectate"), Some(("team-red", true)));
+    }
+
+    #[test]
+    fn refuses_other_paths() {
+        for path in [
+            "/ws",
+            "/admin/",
+            "/canvas",
+            "/ws/team-red/edit",
+            "/ws/a/b/spectate",
+        ] {
+            assert_eq!(route(path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn reads_query_parameters() {
+        let uri: Uri = "/ws/team-red?session=abc.def&spectator&access_token=t=1"
+            .parse()
+            .unwrap();
+        assert_eq!(query_param(&uri, "session"), Some("abc.def"));
+        assert_eq!(query_param(&uri, "spectator"), Some(""));
+        assert_eq!(query_param(&uri, "access_token"), Some("t=1"));
+        assert_eq!(query_param(&uri, "spectate"), None);
+        assert_eq!(query_param(&"/".parse().unwrap(), "session"), None);
+    }
+}
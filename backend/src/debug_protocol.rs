@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::generated::grid::Color;
+
+/// A request sent as a JSON text frame by tooling that doesn't speak Bebop, like
+/// `{"type": "place", "x": 3, "y": 4, "color": "ff4500"}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DebugRequest {
+    /// What the connection joined and what it may do there.
+    Info,
+    /// The color of a pixel, of the grid unless a layer is given.
+    Pixel {
+        #[serde(default)]
+        layer: u32,
+        x: u32,
+        y: u32,
+    },
+    /// Places a pixel like a `LayerPixelPlacement`.
+    Place {
+        #[serde(default)]
+        request_id: u32,
+        #[serde(default)]
+        layer: u32,
+        x: u32,
+        y: u32,
+        color: Color,
+    },
+    /// Takes back the last placement like an `UndoPlacement`.
+    Undo {
+        #[serde(default)]
+        request_id: u32,
+    },
+}
+
+impl DebugRequest {
+    /// Reads the request, or the error to answer it with.
+    pub fn parse(text: &str) -> Result<Self, DebugReply> {
+        serde_json::from_str(text).map_err(|err| DebugReply::Error {
+            message: err.to_string(),
+        })
+    }
+}
+
+/// The answer to a [`DebugRequest`], sent back as a JSON text frame.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DebugReply {
+    Info {
+        canvas: String,
+        width: usize,
+        height: usize,
+        layers: usize,
+        /// Version of the Bebop messages the connection receives.
+        protocol: u32,
+        access: String,
+        /// The id other players see, see `PixelPlaced`.
+        placer: String,
+    },
+    Pixel {
+        layer: u32,
+        x: u32,
+        y: u32,
+        /// `None` when the pixel is transparent or outside the grid.
+        color: Option<Color>,
+    },
+    PlacementResult {
+        request_id: u32,
+        /// One of the `Placement*` status codes of the schema.
+        status: u32,
+        /// Why the placement was refused, in words.
+        notice: Option<String>,
+    },
+    /// The request couldn't be read.
+    Error { message: String },
+}
+
+impl DebugReply {
+    pub fn encode(&self) -> Message {
+        Message::Text(serde_json::to_string(self).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn error_message(text: &str) -> String {
+        match DebugRequest::parse(text) {
+            Err(DebugReply::Error { message }) => message,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_placements() {
+        let request =
+            DebugRequest::parse(r#"{"type": "place", "x": 3, "y": 4, "color": "ff4500"}"#);
+        assert!(matches!(
+            request,
+            Ok(DebugRequest::Place { request_id: 0, layer: 0, x: 3, y: 4, color })
+                if color == Color::new(255, 69, 0)
+        ));
+        let request = DebugRequest::parse(
+            r#"{"type": "place", "request_id": 9, "layer": 2, "x": 0, "y": 1, "color": "000000"}"#,
+        );
+        assert!(matches!(
+            request,
+            Ok(DebugRequest::Place {
+                request_id: 9,
+                layer: 2,
+                x: 0,
+                y: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_the_other_requests() {
+        assert!(matches!(
+            DebugRequest::parse(r#"{"type": "info"}"#),
+            Ok(DebugRequest::Info)
+        ));
+        assert!(matches!(
+            DebugRequest::parse(r#"{"type": "pixel", "x": 5, "y": 6}"#),
+            Ok(DebugRequest::Pixel {
+                layer: 0,
+                x: 5,
+                y: 6
+            })
+        ));
+        assert!(matches!(
+            DebugRequest::parse(r#"{"type": "undo", "request_id": 4}"#),
+            Ok(DebugRequest::Undo { request_id: 4 })
+        ));
+    }
+
+    #[test]
+    fn answers_malformed_requests_with_an_error() {
+        assert!(error_message("place 3 4").contains("expected value"));
+        assert!(error_message(r#"{"type": "paint"}"#).contains("unknown variant `paint`"));
+        assert!(
+            error_message(r#"{"type": "place", "x": 3, "color": "ff4500"}"#)
+                .contains("missing field `y`")
+        );
+        assert!(!error_message(r#"{"type": "place", "x": 3, "y": 4, "color": "red"}"#).is_empty());
+        assert!(!error_message(r#"{"type": "pixel", "x": -1, "y": 0}"#).is_empty());
+    }
+
+    #[test]
+    fn encodes_replies_as_tagged_json() {
+        let reply = DebugReply::Error {
+            message: "nope".to_string(),
+        };
+        let text = match reply.encode() {
+            Message::Text(text) => text,
+            other => panic!("unexpected {:?}", other),
+        };
+        let value: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(value, json!({"type": "error", "message": "nope"}));
+
+        let reply = DebugReply::Pixel {
+            layer: 0,
+            x: 1,
+            y: 2,
+            color: Some(Color::new(255, 69, 0)),
+        };
+        let value: Value = match reply.encode() {
+            Message::Text(text) => serde_json::from_str(&text).unwrap(),
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(
+            value,
+            json!({"type": "pixel", "layer": 0, "x": 1, "y": 2, "color": "ff4500"})
+        );
+    }
+}
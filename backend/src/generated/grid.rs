@@ -4,7 +4,9 @@ use bebop::FixedSized as _;
 use core::convert::TryInto as _;
 use std::io::Write as _;
 
-pub const PROTOCOL_VERSION: u32 = 1;
+pub const PROTOCOL_VERSION: u32 = 3;
+
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
 
 pub const GRID_OPCODE: u32 = 1;
 
@@ -12,6 +14,98 @@ pub const PIXEL_OPCODE: u32 = 2;
 
 pub const DELTA_GRID_OPCODE: u32 = 3;
 
+pub const PLACEMENT_RESULT_OPCODE: u32 = 4;
+
+pub const PIXEL_PLACEMENT_OPCODE: u32 = 5;
+
+pub const SESSION_TOKEN_OPCODE: u32 = 6;
+
+pub const ADMIN_COMMAND_OPCODE: u32 = 7;
+
+pub const ADMIN_RESULT_OPCODE: u32 = 8;
+
+pub const PALETTE_OPCODE: u32 = 9;
+
+pub const CANVAS_SCHEDULE_OPCODE: u32 = 10;
+
+pub const LAYERS_OPCODE: u32 = 11;
+
+pub const LAYER_GRID_OPCODE: u32 = 12;
+
+pub const LAYER_DELTA_OPCODE: u32 = 13;
+
+pub const LAYER_PIXEL_PLACEMENT_OPCODE: u32 = 14;
+
+pub const RECT_FILL_OPCODE: u32 = 15;
+
+pub const PIXEL_LIST_OPCODE: u32 = 16;
+
+pub const BITMAP_OPCODE: u32 = 17;
+
+pub const TEMPLATES_OPCODE: u32 = 18;
+
+pub const TEMPLATE_REQUEST_OPCODE: u32 = 19;
+
+pub const TEMPLATE_OPCODE: u32 = 20;
+
+pub const TEMPLATE_UPLOAD_OPCODE: u32 = 21;
+
+pub const UNDO_PLACEMENT_OPCODE: u32 = 22;
+
+pub const PIXEL_PLACED_OPCODE: u32 = 23;
+
+pub const CLIENT_HELLO_OPCODE: u32 = 24;
+
+pub const SERVER_HELLO_OPCODE: u32 = 25;
+
+pub const SERVER_NOTICE_OPCODE: u32 = 26;
+
+pub const PLACEMENT_ACCEPTED: u32 = 0;
+
+pub const PLACEMENT_SERVER_BUSY: u32 = 1;
+
+pub const PLACEMENT_OUT_OF_BOUNDS: u32 = 2;
+
+pub const PLACEMENT_RATE_LIMITED: u32 = 3;
+
+pub const PLACEMENT_BANNED: u32 = 4;
+
+pub const PLACEMENT_CANVAS_LOCKED: u32 = 5;
+
+pub const PLACEMENT_UNAUTHORIZED: u32 = 6;
+
+pub const PLACEMENT_INVALID_COLOR: u32 = 7;
+
+pub const PLACEMENT_CANVAS_ENDED: u32 = 8;
+
+pub const PLACEMENT_PROTECTED: u32 = 9;
+
+pub const PLACEMENT_LAYER_RESTRICTED: u32 = 10;
+
+pub const PLACEMENT_NOTHING_TO_UNDO: u32 = 11;
+
+pub const PLACEMENT_SPECTATING: u32 = 12;
+
+pub const NOTICE_PLACEMENT_REJECTED: u32 = 1;
+
+pub const NOTICE_COOLDOWN: u32 = 2;
+
+pub const NOTICE_ANNOUNCEMENT: u32 = 3;
+
+pub const NOTICE_SHUTDOWN: u32 = 4;
+
+pub const NOTICE_INFO: u32 = 0;
+
+pub const NOTICE_WARNING: u32 = 1;
+
+pub const NOTICE_ERROR: u32 = 2;
+
+pub const CANVAS_OPEN: u32 = 0;
+
+pub const CANVAS_LOCKED: u32 = 1;
+
+pub const CANVAS_ENDED: u32 = 2;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct BebopData<'raw> {
     pub protocol_version: u32,
@@ -279,200 +373,2549 @@ impl<'raw> ::bebop::SubRecord<'raw> for DeltaGrid<'raw> {
 
 impl<'raw> ::bebop::Record<'raw> for DeltaGrid<'raw> {}
 
-#[cfg(feature = "bebop-owned-all")]
-pub mod owned {
-    #![allow(warnings)]
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct PixelPlacement {
+    pub request_id: u32,
+    pub pixel: Pixel,
+}
 
-    use bebop::FixedSized as _;
-    use core::convert::TryInto as _;
-    use std::io::Write as _;
+impl ::bebop::FixedSized for PixelPlacement {}
 
-    pub use super::PROTOCOL_VERSION;
+impl<'raw> ::bebop::SubRecord<'raw> for PixelPlacement {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
 
-    pub use super::GRID_OPCODE;
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
 
-    pub use super::PIXEL_OPCODE;
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.request_id)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.pixel)._serialize_chained(dest)?
+        )
+    });
 
-    pub use super::DELTA_GRID_OPCODE;
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
 
-    #[derive(Clone, Debug, PartialEq)]
-    pub struct BebopData {
-        pub protocol_version: u32,
-        pub opcode: u32,
-        pub encoded_data: ::std::vec::Vec<u8>,
-    }
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
 
-    impl<'raw> ::core::convert::From<super::BebopData<'raw>> for BebopData {
-        fn from(value: super::BebopData) -> Self {
+        Ok((
+            i,
             Self {
-                protocol_version: value.protocol_version,
-                opcode: value.opcode,
-                encoded_data: value.encoded_data.iter().map(|value| value).collect(),
-            }
-        }
+                request_id: v0,
+                pixel: v1,
+            },
+        ))
     }
+}
 
-    impl<'raw> ::bebop::SubRecord<'raw> for BebopData {
-        const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
-            + <u32>::MIN_SERIALIZED_SIZE
-            + <::std::vec::Vec<u8>>::MIN_SERIALIZED_SIZE;
-
-        #[inline]
-        fn serialized_size(&self) -> usize {
-            self.protocol_version.serialized_size()
-                + self.opcode.serialized_size()
-                + self.encoded_data.serialized_size()
-        }
+impl<'raw> ::bebop::Record<'raw> for PixelPlacement {}
 
-        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
-            Ok(
-                zelf.protocol_version._serialize_chained(dest)? +
-                zelf.opcode._serialize_chained(dest)? +
-                zelf.encoded_data._serialize_chained(dest)?
-            )
-        });
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct PlacementResult {
+    pub request_id: u32,
+    pub status: u32,
+}
 
-        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
-            let mut i = 0;
-            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
-                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
-                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
-            }
+impl ::bebop::FixedSized for PlacementResult {}
 
-            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
-            i += read;
-            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
-            i += read;
-            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
-            i += read;
+impl<'raw> ::bebop::SubRecord<'raw> for PlacementResult {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
 
-            Ok((
-                i,
-                Self {
-                    protocol_version: v0,
-                    opcode: v1,
-                    encoded_data: v2,
-                },
-            ))
-        }
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
     }
 
-    impl<'raw> ::bebop::Record<'raw> for BebopData {}
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.request_id)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.status)._serialize_chained(dest)?
+        )
+    });
 
-    #[derive(Clone, Debug, PartialEq)]
-    pub struct Grid {
-        pub rows: ::std::vec::Vec<Row>,
-    }
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
 
-    impl<'raw> ::core::convert::From<super::Grid<'raw>> for Grid {
-        fn from(value: super::Grid) -> Self {
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
             Self {
-                rows: value.rows.into_iter().map(|value| value.into()).collect(),
-            }
-        }
+                request_id: v0,
+                status: v1,
+            },
+        ))
     }
+}
 
-    impl<'raw> ::bebop::SubRecord<'raw> for Grid {
-        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<Row>>::MIN_SERIALIZED_SIZE;
+impl<'raw> ::bebop::Record<'raw> for PlacementResult {}
 
-        #[inline]
-        fn serialized_size(&self) -> usize {
-            self.rows.serialized_size()
-        }
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionToken<'raw> {
+    pub token: &'raw str,
+}
 
-        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
-            Ok(
-                zelf.rows._serialize_chained(dest)?
-            )
-        });
+impl<'raw> ::bebop::SubRecord<'raw> for SessionToken<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <&'raw str>::MIN_SERIALIZED_SIZE;
 
-        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
-            let mut i = 0;
-            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
-                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
-                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
-            }
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.token.serialized_size()
+    }
 
-            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
-            i += read;
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.token._serialize_chained(dest)?
+        )
+    });
 
-            Ok((i, Self { rows: v0 }))
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
         }
-    }
 
-    impl<'raw> ::bebop::Record<'raw> for Grid {}
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
 
-    #[derive(Clone, Debug, PartialEq)]
-    pub struct Row {
-        pub pixels: ::std::vec::Vec<Color>,
+        Ok((i, Self { token: v0 }))
     }
+}
 
-    impl<'raw> ::core::convert::From<super::Row<'raw>> for Row {
-        fn from(value: super::Row) -> Self {
-            Self {
-                pixels: value.pixels.iter().map(|value| value).collect(),
-            }
-        }
+impl<'raw> ::bebop::Record<'raw> for SessionToken<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette<'raw> {
+    pub colors: ::bebop::SliceWrapper<'raw, Color>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Palette<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <::bebop::SliceWrapper<'raw, Color>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.colors.serialized_size()
     }
 
-    impl<'raw> ::bebop::SubRecord<'raw> for Row {
-        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<Color>>::MIN_SERIALIZED_SIZE;
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.colors._serialize_chained(dest)?
+        )
+    });
 
-        #[inline]
-        fn serialized_size(&self) -> usize {
-            self.pixels.serialized_size()
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
         }
 
-        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
-            Ok(
-                zelf.pixels._serialize_chained(dest)?
-            )
-        });
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
 
-        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
-            let mut i = 0;
-            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
-                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+        Ok((i, Self { colors: v0 }))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Palette<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdminCommand<'raw> {
+    pub request_id: u32,
+    pub command: &'raw str,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for AdminCommand<'raw> {
+    const MIN_SERIALIZED_SIZE: usize =
+        <u32>::MIN_SERIALIZED_SIZE + <&'raw str>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.request_id.serialized_size() + self.command.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.request_id._serialize_chained(dest)? +
+            zelf.command._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                request_id: v0,
+                command: v1,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for AdminCommand<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdminResult<'raw> {
+    pub request_id: u32,
+    pub success: bool,
+    pub message: &'raw str,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for AdminResult<'raw> {
+    const MIN_SERIALIZED_SIZE: usize =
+        <u32>::MIN_SERIALIZED_SIZE + <bool>::MIN_SERIALIZED_SIZE + <&'raw str>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.request_id.serialized_size()
+            + self.success.serialized_size()
+            + self.message.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.request_id._serialize_chained(dest)? +
+            zelf.success._serialize_chained(dest)? +
+            zelf.message._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                request_id: v0,
+                success: v1,
+                message: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for AdminResult<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct CanvasSchedule {
+    pub status: u32,
+    pub starts_at: u64,
+    pub ends_at: u64,
+}
+
+impl ::bebop::FixedSized for CanvasSchedule {}
+
+impl<'raw> ::bebop::SubRecord<'raw> for CanvasSchedule {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.status)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.starts_at)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.ends_at)._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                status: v0,
+                starts_at: v1,
+                ends_at: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for CanvasSchedule {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layer<'raw> {
+    pub name: &'raw str,
+    pub restricted: bool,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Layer<'raw> {
+    const MIN_SERIALIZED_SIZE: usize =
+        <&'raw str>::MIN_SERIALIZED_SIZE + <bool>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.name.serialized_size() + self.restricted.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.name._serialize_chained(dest)? +
+            zelf.restricted._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                name: v0,
+                restricted: v1,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Layer<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layers<'raw> {
+    pub layers: ::std::vec::Vec<Layer<'raw>>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Layers<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<Layer<'raw>>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.layers.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.layers._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((i, Self { layers: v0 }))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Layers<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerGrid<'raw> {
+    pub layer: u32,
+    pub pixels: ::bebop::SliceWrapper<'raw, Pixel>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for LayerGrid<'raw> {
+    const MIN_SERIALIZED_SIZE: usize =
+        <u32>::MIN_SERIALIZED_SIZE + <::bebop::SliceWrapper<'raw, Pixel>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.layer.serialized_size() + self.pixels.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.layer._serialize_chained(dest)? +
+            zelf.pixels._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                layer: v0,
+                pixels: v1,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for LayerGrid<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct Position {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl ::bebop::FixedSized for Position {}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Position {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.x)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.y)._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((i, Self { x: v0, y: v1 }))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Position {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerDelta<'raw> {
+    pub layer: u32,
+    pub painted: ::bebop::SliceWrapper<'raw, Pixel>,
+    pub erased: ::bebop::SliceWrapper<'raw, Position>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for LayerDelta<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+        + <::bebop::SliceWrapper<'raw, Pixel>>::MIN_SERIALIZED_SIZE
+        + <::bebop::SliceWrapper<'raw, Position>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.layer.serialized_size()
+            + self.painted.serialized_size()
+            + self.erased.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.layer._serialize_chained(dest)? +
+            zelf.painted._serialize_chained(dest)? +
+            zelf.erased._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                layer: v0,
+                painted: v1,
+                erased: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for LayerDelta<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct LayerPixelPlacement {
+    pub request_id: u32,
+    pub layer: u32,
+    pub pixel: Pixel,
+}
+
+impl ::bebop::FixedSized for LayerPixelPlacement {}
+
+impl<'raw> ::bebop::SubRecord<'raw> for LayerPixelPlacement {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.request_id)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.layer)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.pixel)._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                request_id: v0,
+                layer: v1,
+                pixel: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for LayerPixelPlacement {}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct RectFill {
+    pub request_id: u32,
+    pub layer: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub color: Color,
+}
+
+impl ::bebop::FixedSized for RectFill {}
+
+impl<'raw> ::bebop::SubRecord<'raw> for RectFill {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.request_id)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.layer)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.x)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.y)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.width)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.height)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.color)._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v3) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v4) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v5) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v6) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                request_id: v0,
+                layer: v1,
+                x: v2,
+                y: v3,
+                width: v4,
+                height: v5,
+                color: v6,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for RectFill {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelList<'raw> {
+    pub request_id: u32,
+    pub layer: u32,
+    pub pixels: ::bebop::SliceWrapper<'raw, Pixel>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for PixelList<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <::bebop::SliceWrapper<'raw, Pixel>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.request_id.serialized_size()
+            + self.layer.serialized_size()
+            + self.pixels.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.request_id._serialize_chained(dest)? +
+            zelf.layer._serialize_chained(dest)? +
+            zelf.pixels._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                request_id: v0,
+                layer: v1,
+                pixels: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for PixelList<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct ColorRun {
+    pub length: u32,
+    pub color: Color,
+}
+
+impl ::bebop::FixedSized for ColorRun {}
+
+impl<'raw> ::bebop::SubRecord<'raw> for ColorRun {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.length)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.color)._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                length: v0,
+                color: v1,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for ColorRun {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bitmap<'raw> {
+    pub request_id: u32,
+    pub layer: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub runs: ::bebop::SliceWrapper<'raw, ColorRun>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Bitmap<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <::bebop::SliceWrapper<'raw, ColorRun>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.request_id.serialized_size()
+            + self.layer.serialized_size()
+            + self.x.serialized_size()
+            + self.y.serialized_size()
+            + self.width.serialized_size()
+            + self.runs.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.request_id._serialize_chained(dest)? +
+            zelf.layer._serialize_chained(dest)? +
+            zelf.x._serialize_chained(dest)? +
+            zelf.y._serialize_chained(dest)? +
+            zelf.width._serialize_chained(dest)? +
+            zelf.runs._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v3) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v4) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v5) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                request_id: v0,
+                layer: v1,
+                x: v2,
+                y: v3,
+                width: v4,
+                runs: v5,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Bitmap<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateInfo<'raw> {
+    pub name: &'raw str,
+    pub layer: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub differing: u32,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for TemplateInfo<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <&'raw str>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.name.serialized_size()
+            + self.layer.serialized_size()
+            + self.x.serialized_size()
+            + self.y.serialized_size()
+            + self.width.serialized_size()
+            + self.height.serialized_size()
+            + self.differing.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.name._serialize_chained(dest)? +
+            zelf.layer._serialize_chained(dest)? +
+            zelf.x._serialize_chained(dest)? +
+            zelf.y._serialize_chained(dest)? +
+            zelf.width._serialize_chained(dest)? +
+            zelf.height._serialize_chained(dest)? +
+            zelf.differing._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v3) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v4) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v5) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v6) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                name: v0,
+                layer: v1,
+                x: v2,
+                y: v3,
+                width: v4,
+                height: v5,
+                differing: v6,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for TemplateInfo<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Templates<'raw> {
+    pub templates: ::std::vec::Vec<TemplateInfo<'raw>>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Templates<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<TemplateInfo<'raw>>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.templates.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.templates._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((i, Self { templates: v0 }))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Templates<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateRequest<'raw> {
+    pub name: &'raw str,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for TemplateRequest<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <&'raw str>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.name.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.name._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((i, Self { name: v0 }))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for TemplateRequest<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct TemplateRun {
+    pub length: u32,
+    pub alpha: u8,
+    pub color: Color,
+}
+
+impl ::bebop::FixedSized for TemplateRun {}
+
+impl<'raw> ::bebop::SubRecord<'raw> for TemplateRun {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.length)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.alpha)._serialize_chained(dest)? +
+            ::bebop::packed_read!(zelf.color)._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                length: v0,
+                alpha: v1,
+                color: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for TemplateRun {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Template<'raw> {
+    pub name: &'raw str,
+    pub layer: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub runs: ::bebop::SliceWrapper<'raw, TemplateRun>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for Template<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <&'raw str>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <u32>::MIN_SERIALIZED_SIZE
+        + <::bebop::SliceWrapper<'raw, TemplateRun>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.name.serialized_size()
+            + self.layer.serialized_size()
+            + self.x.serialized_size()
+            + self.y.serialized_size()
+            + self.width.serialized_size()
+            + self.runs.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.name._serialize_chained(dest)? +
+            zelf.layer._serialize_chained(dest)? +
+            zelf.x._serialize_chained(dest)? +
+            zelf.y._serialize_chained(dest)? +
+            zelf.width._serialize_chained(dest)? +
+            zelf.runs._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v3) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v4) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v5) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                name: v0,
+                layer: v1,
+                x: v2,
+                y: v3,
+                width: v4,
+                runs: v5,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for Template<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateUpload<'raw> {
+    pub request_id: u32,
+    pub canvas: &'raw str,
+    pub template: Template<'raw>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for TemplateUpload<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+        + <&'raw str>::MIN_SERIALIZED_SIZE
+        + <Template<'raw>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.request_id.serialized_size()
+            + self.canvas.serialized_size()
+            + self.template.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.request_id._serialize_chained(dest)? +
+            zelf.canvas._serialize_chained(dest)? +
+            zelf.template._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                request_id: v0,
+                canvas: v1,
+                template: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for TemplateUpload<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct UndoPlacement {
+    pub request_id: u32,
+}
+
+impl ::bebop::FixedSized for UndoPlacement {}
+
+impl<'raw> ::bebop::SubRecord<'raw> for UndoPlacement {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.request_id)._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((i, Self { request_id: v0 }))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for UndoPlacement {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelPlaced<'raw> {
+    pub layer: u32,
+    pub pixel: Pixel,
+    pub placed_at: u64,
+    pub placer: &'raw str,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for PixelPlaced<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+        + <Pixel>::MIN_SERIALIZED_SIZE
+        + <u64>::MIN_SERIALIZED_SIZE
+        + <&'raw str>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.layer.serialized_size()
+            + self.pixel.serialized_size()
+            + self.placed_at.serialized_size()
+            + self.placer.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.layer._serialize_chained(dest)? +
+            zelf.pixel._serialize_chained(dest)? +
+            zelf.placed_at._serialize_chained(dest)? +
+            zelf.placer._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v3) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                layer: v0,
+                pixel: v1,
+                placed_at: v2,
+                placer: v3,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for PixelPlaced<'raw> {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientHello<'raw> {
+    pub versions: ::bebop::SliceWrapper<'raw, u32>,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for ClientHello<'raw> {
+    const MIN_SERIALIZED_SIZE: usize = <::bebop::SliceWrapper<'raw, u32>>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.versions.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.versions._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((i, Self { versions: v0 }))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for ClientHello<'raw> {}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+#[repr(packed)]
+pub struct ServerHello {
+    pub version: u32,
+}
+
+impl ::bebop::FixedSized for ServerHello {}
+
+impl<'raw> ::bebop::SubRecord<'raw> for ServerHello {
+    const MIN_SERIALIZED_SIZE: usize = Self::SERIALIZED_SIZE;
+    const EXACT_SERIALIZED_SIZE: Option<usize> = Some(Self::SERIALIZED_SIZE);
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        Self::SERIALIZED_SIZE
+    }
+
+    ::bebop::define_serialize_chained!(*Self => |zelf, dest| {
+        Ok(
+            ::bebop::packed_read!(zelf.version)._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((i, Self { version: v0 }))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for ServerHello {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerNotice<'raw> {
+    pub code: u32,
+    pub severity: u32,
+    pub text: &'raw str,
+}
+
+impl<'raw> ::bebop::SubRecord<'raw> for ServerNotice<'raw> {
+    const MIN_SERIALIZED_SIZE: usize =
+        <u32>::MIN_SERIALIZED_SIZE + <u32>::MIN_SERIALIZED_SIZE + <&'raw str>::MIN_SERIALIZED_SIZE;
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.code.serialized_size() + self.severity.serialized_size() + self.text.serialized_size()
+    }
+
+    ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+        Ok(
+            zelf.code._serialize_chained(dest)? +
+            zelf.severity._serialize_chained(dest)? +
+            zelf.text._serialize_chained(dest)?
+        )
+    });
+
+    fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+        let mut i = 0;
+        if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+            let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+            return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+        }
+
+        let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+        let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+        i += read;
+
+        Ok((
+            i,
+            Self {
+                code: v0,
+                severity: v1,
+                text: v2,
+            },
+        ))
+    }
+}
+
+impl<'raw> ::bebop::Record<'raw> for ServerNotice<'raw> {}
+
+#[cfg(feature = "bebop-owned-all")]
+pub mod owned {
+    #![allow(warnings)]
+
+    use bebop::FixedSized as _;
+    use core::convert::TryInto as _;
+    use std::io::Write as _;
+
+    pub use super::PROTOCOL_VERSION;
+
+    pub use super::MIN_PROTOCOL_VERSION;
+
+    pub use super::GRID_OPCODE;
+
+    pub use super::PIXEL_OPCODE;
+
+    pub use super::DELTA_GRID_OPCODE;
+
+    pub use super::PLACEMENT_RESULT_OPCODE;
+
+    pub use super::PIXEL_PLACEMENT_OPCODE;
+
+    pub use super::SESSION_TOKEN_OPCODE;
+
+    pub use super::ADMIN_COMMAND_OPCODE;
+
+    pub use super::ADMIN_RESULT_OPCODE;
+
+    pub use super::PALETTE_OPCODE;
+
+    pub use super::CANVAS_SCHEDULE_OPCODE;
+
+    pub use super::LAYERS_OPCODE;
+
+    pub use super::LAYER_GRID_OPCODE;
+
+    pub use super::LAYER_DELTA_OPCODE;
+
+    pub use super::LAYER_PIXEL_PLACEMENT_OPCODE;
+
+    pub use super::RECT_FILL_OPCODE;
+
+    pub use super::PIXEL_LIST_OPCODE;
+
+    pub use super::BITMAP_OPCODE;
+
+    pub use super::TEMPLATES_OPCODE;
+
+    pub use super::TEMPLATE_REQUEST_OPCODE;
+
+    pub use super::TEMPLATE_OPCODE;
+
+    pub use super::TEMPLATE_UPLOAD_OPCODE;
+
+    pub use super::UNDO_PLACEMENT_OPCODE;
+
+    pub use super::PIXEL_PLACED_OPCODE;
+
+    pub use super::CLIENT_HELLO_OPCODE;
+
+    pub use super::SERVER_HELLO_OPCODE;
+
+    pub use super::SERVER_NOTICE_OPCODE;
+
+    pub use super::PLACEMENT_ACCEPTED;
+
+    pub use super::PLACEMENT_SERVER_BUSY;
+
+    pub use super::PLACEMENT_OUT_OF_BOUNDS;
+
+    pub use super::PLACEMENT_RATE_LIMITED;
+
+    pub use super::PLACEMENT_BANNED;
+
+    pub use super::PLACEMENT_CANVAS_LOCKED;
+
+    pub use super::PLACEMENT_UNAUTHORIZED;
+
+    pub use super::PLACEMENT_INVALID_COLOR;
+
+    pub use super::PLACEMENT_CANVAS_ENDED;
+
+    pub use super::PLACEMENT_PROTECTED;
+
+    pub use super::PLACEMENT_LAYER_RESTRICTED;
+
+    pub use super::PLACEMENT_NOTHING_TO_UNDO;
+
+    pub use super::PLACEMENT_SPECTATING;
+
+    pub use super::NOTICE_PLACEMENT_REJECTED;
+
+    pub use super::NOTICE_COOLDOWN;
+
+    pub use super::NOTICE_ANNOUNCEMENT;
+
+    pub use super::NOTICE_SHUTDOWN;
+
+    pub use super::NOTICE_INFO;
+
+    pub use super::NOTICE_WARNING;
+
+    pub use super::NOTICE_ERROR;
+
+    pub use super::CANVAS_OPEN;
+
+    pub use super::CANVAS_LOCKED;
+
+    pub use super::CANVAS_ENDED;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BebopData {
+        pub protocol_version: u32,
+        pub opcode: u32,
+        pub encoded_data: ::std::vec::Vec<u8>,
+    }
+
+    impl<'raw> ::core::convert::From<super::BebopData<'raw>> for BebopData {
+        fn from(value: super::BebopData) -> Self {
+            Self {
+                protocol_version: value.protocol_version,
+                opcode: value.opcode,
+                encoded_data: value.encoded_data.iter().map(|value| value).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for BebopData {
+        const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <::std::vec::Vec<u8>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.protocol_version.serialized_size()
+                + self.opcode.serialized_size()
+                + self.encoded_data.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.protocol_version._serialize_chained(dest)? +
+                zelf.opcode._serialize_chained(dest)? +
+                zelf.encoded_data._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    protocol_version: v0,
+                    opcode: v1,
+                    encoded_data: v2,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for BebopData {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Grid {
+        pub rows: ::std::vec::Vec<Row>,
+    }
+
+    impl<'raw> ::core::convert::From<super::Grid<'raw>> for Grid {
+        fn from(value: super::Grid) -> Self {
+            Self {
+                rows: value.rows.into_iter().map(|value| value.into()).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Grid {
+        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<Row>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.rows.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.rows._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((i, Self { rows: v0 }))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for Grid {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Row {
+        pub pixels: ::std::vec::Vec<Color>,
+    }
+
+    impl<'raw> ::core::convert::From<super::Row<'raw>> for Row {
+        fn from(value: super::Row) -> Self {
+            Self {
+                pixels: value.pixels.iter().map(|value| value).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Row {
+        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<Color>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.pixels.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.pixels._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((i, Self { pixels: v0 }))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for Row {}
+
+    pub use super::Pixel;
+
+    pub use super::Color;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct DeltaGrid {
+        pub delta: ::std::vec::Vec<Pixel>,
+    }
+
+    impl<'raw> ::core::convert::From<super::DeltaGrid<'raw>> for DeltaGrid {
+        fn from(value: super::DeltaGrid) -> Self {
+            Self {
+                delta: value.delta.iter().map(|value| value).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for DeltaGrid {
+        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<Pixel>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.delta.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.delta._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((i, Self { delta: v0 }))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for DeltaGrid {}
+
+    pub use super::PixelPlacement;
+
+    pub use super::PlacementResult;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct SessionToken {
+        pub token: ::std::string::String,
+    }
+
+    impl<'raw> ::core::convert::From<super::SessionToken<'raw>> for SessionToken {
+        fn from(value: super::SessionToken) -> Self {
+            Self {
+                token: value.token.into(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for SessionToken {
+        const MIN_SERIALIZED_SIZE: usize = <::std::string::String>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.token.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.token._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((i, Self { token: v0 }))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for SessionToken {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Palette {
+        pub colors: ::std::vec::Vec<Color>,
+    }
+
+    impl<'raw> ::core::convert::From<super::Palette<'raw>> for Palette {
+        fn from(value: super::Palette) -> Self {
+            Self {
+                colors: value.colors.iter().map(|value| value).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Palette {
+        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<Color>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.colors.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.colors._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((i, Self { colors: v0 }))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for Palette {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct AdminCommand {
+        pub request_id: u32,
+        pub command: ::std::string::String,
+    }
+
+    impl<'raw> ::core::convert::From<super::AdminCommand<'raw>> for AdminCommand {
+        fn from(value: super::AdminCommand) -> Self {
+            Self {
+                request_id: value.request_id,
+                command: value.command.into(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for AdminCommand {
+        const MIN_SERIALIZED_SIZE: usize =
+            <u32>::MIN_SERIALIZED_SIZE + <::std::string::String>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.request_id.serialized_size() + self.command.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.request_id._serialize_chained(dest)? +
+                zelf.command._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    request_id: v0,
+                    command: v1,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for AdminCommand {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct AdminResult {
+        pub request_id: u32,
+        pub success: bool,
+        pub message: ::std::string::String,
+    }
+
+    impl<'raw> ::core::convert::From<super::AdminResult<'raw>> for AdminResult {
+        fn from(value: super::AdminResult) -> Self {
+            Self {
+                request_id: value.request_id,
+                success: value.success,
+                message: value.message.into(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for AdminResult {
+        const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+            + <bool>::MIN_SERIALIZED_SIZE
+            + <::std::string::String>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.request_id.serialized_size()
+                + self.success.serialized_size()
+                + self.message.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.request_id._serialize_chained(dest)? +
+                zelf.success._serialize_chained(dest)? +
+                zelf.message._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    request_id: v0,
+                    success: v1,
+                    message: v2,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for AdminResult {}
+
+    pub use super::CanvasSchedule;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Layer {
+        pub name: ::std::string::String,
+        pub restricted: bool,
+    }
+
+    impl<'raw> ::core::convert::From<super::Layer<'raw>> for Layer {
+        fn from(value: super::Layer) -> Self {
+            Self {
+                name: value.name.into(),
+                restricted: value.restricted,
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Layer {
+        const MIN_SERIALIZED_SIZE: usize =
+            <::std::string::String>::MIN_SERIALIZED_SIZE + <bool>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.name.serialized_size() + self.restricted.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.name._serialize_chained(dest)? +
+                zelf.restricted._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    name: v0,
+                    restricted: v1,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for Layer {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Layers {
+        pub layers: ::std::vec::Vec<Layer>,
+    }
+
+    impl<'raw> ::core::convert::From<super::Layers<'raw>> for Layers {
+        fn from(value: super::Layers) -> Self {
+            Self {
+                layers: value.layers.into_iter().map(|value| value.into()).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Layers {
+        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<Layer>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.layers.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.layers._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((i, Self { layers: v0 }))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for Layers {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct LayerGrid {
+        pub layer: u32,
+        pub pixels: ::std::vec::Vec<Pixel>,
+    }
+
+    impl<'raw> ::core::convert::From<super::LayerGrid<'raw>> for LayerGrid {
+        fn from(value: super::LayerGrid) -> Self {
+            Self {
+                layer: value.layer,
+                pixels: value.pixels.iter().map(|value| value).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for LayerGrid {
+        const MIN_SERIALIZED_SIZE: usize =
+            <u32>::MIN_SERIALIZED_SIZE + <::std::vec::Vec<Pixel>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.layer.serialized_size() + self.pixels.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.layer._serialize_chained(dest)? +
+                zelf.pixels._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    layer: v0,
+                    pixels: v1,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for LayerGrid {}
+
+    pub use super::Position;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct LayerDelta {
+        pub layer: u32,
+        pub painted: ::std::vec::Vec<Pixel>,
+        pub erased: ::std::vec::Vec<Position>,
+    }
+
+    impl<'raw> ::core::convert::From<super::LayerDelta<'raw>> for LayerDelta {
+        fn from(value: super::LayerDelta) -> Self {
+            Self {
+                layer: value.layer,
+                painted: value.painted.iter().map(|value| value).collect(),
+                erased: value.erased.iter().map(|value| value).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for LayerDelta {
+        const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+            + <::std::vec::Vec<Pixel>>::MIN_SERIALIZED_SIZE
+            + <::std::vec::Vec<Position>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.layer.serialized_size()
+                + self.painted.serialized_size()
+                + self.erased.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.layer._serialize_chained(dest)? +
+                zelf.painted._serialize_chained(dest)? +
+                zelf.erased._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    layer: v0,
+                    painted: v1,
+                    erased: v2,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for LayerDelta {}
+
+    pub use super::LayerPixelPlacement;
+
+    pub use super::RectFill;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct PixelList {
+        pub request_id: u32,
+        pub layer: u32,
+        pub pixels: ::std::vec::Vec<Pixel>,
+    }
+
+    impl<'raw> ::core::convert::From<super::PixelList<'raw>> for PixelList {
+        fn from(value: super::PixelList) -> Self {
+            Self {
+                request_id: value.request_id,
+                layer: value.layer,
+                pixels: value.pixels.iter().map(|value| value).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for PixelList {
+        const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <::std::vec::Vec<Pixel>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.request_id.serialized_size()
+                + self.layer.serialized_size()
+                + self.pixels.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.request_id._serialize_chained(dest)? +
+                zelf.layer._serialize_chained(dest)? +
+                zelf.pixels._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    request_id: v0,
+                    layer: v1,
+                    pixels: v2,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for PixelList {}
+
+    pub use super::ColorRun;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Bitmap {
+        pub request_id: u32,
+        pub layer: u32,
+        pub x: u32,
+        pub y: u32,
+        pub width: u32,
+        pub runs: ::std::vec::Vec<ColorRun>,
+    }
+
+    impl<'raw> ::core::convert::From<super::Bitmap<'raw>> for Bitmap {
+        fn from(value: super::Bitmap) -> Self {
+            Self {
+                request_id: value.request_id,
+                layer: value.layer,
+                x: value.x,
+                y: value.y,
+                width: value.width,
+                runs: value.runs.iter().map(|value| value).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Bitmap {
+        const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <::std::vec::Vec<ColorRun>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.request_id.serialized_size()
+                + self.layer.serialized_size()
+                + self.x.serialized_size()
+                + self.y.serialized_size()
+                + self.width.serialized_size()
+                + self.runs.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.request_id._serialize_chained(dest)? +
+                zelf.layer._serialize_chained(dest)? +
+                zelf.x._serialize_chained(dest)? +
+                zelf.y._serialize_chained(dest)? +
+                zelf.width._serialize_chained(dest)? +
+                zelf.runs._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v3) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v4) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v5) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    request_id: v0,
+                    layer: v1,
+                    x: v2,
+                    y: v3,
+                    width: v4,
+                    runs: v5,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for Bitmap {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct TemplateInfo {
+        pub name: ::std::string::String,
+        pub layer: u32,
+        pub x: u32,
+        pub y: u32,
+        pub width: u32,
+        pub height: u32,
+        pub differing: u32,
+    }
+
+    impl<'raw> ::core::convert::From<super::TemplateInfo<'raw>> for TemplateInfo {
+        fn from(value: super::TemplateInfo) -> Self {
+            Self {
+                name: value.name.into(),
+                layer: value.layer,
+                x: value.x,
+                y: value.y,
+                width: value.width,
+                height: value.height,
+                differing: value.differing,
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for TemplateInfo {
+        const MIN_SERIALIZED_SIZE: usize = <::std::string::String>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.name.serialized_size()
+                + self.layer.serialized_size()
+                + self.x.serialized_size()
+                + self.y.serialized_size()
+                + self.width.serialized_size()
+                + self.height.serialized_size()
+                + self.differing.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.name._serialize_chained(dest)? +
+                zelf.layer._serialize_chained(dest)? +
+                zelf.x._serialize_chained(dest)? +
+                zelf.y._serialize_chained(dest)? +
+                zelf.width._serialize_chained(dest)? +
+                zelf.height._serialize_chained(dest)? +
+                zelf.differing._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v3) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v4) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v5) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v6) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    name: v0,
+                    layer: v1,
+                    x: v2,
+                    y: v3,
+                    width: v4,
+                    height: v5,
+                    differing: v6,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for TemplateInfo {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Templates {
+        pub templates: ::std::vec::Vec<TemplateInfo>,
+    }
+
+    impl<'raw> ::core::convert::From<super::Templates<'raw>> for Templates {
+        fn from(value: super::Templates) -> Self {
+            Self {
+                templates: value
+                    .templates
+                    .into_iter()
+                    .map(|value| value.into())
+                    .collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for Templates {
+        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<TemplateInfo>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.templates.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.templates._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
                 return Err(::bebop::DeserializeError::MoreDataExpected(missing));
             }
 
             let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
             i += read;
 
-            Ok((i, Self { pixels: v0 }))
+            Ok((i, Self { templates: v0 }))
         }
     }
 
-    impl<'raw> ::bebop::Record<'raw> for Row {}
+    impl<'raw> ::bebop::Record<'raw> for Templates {}
 
-    pub use super::Pixel;
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct TemplateRequest {
+        pub name: ::std::string::String,
+    }
 
-    pub use super::Color;
+    impl<'raw> ::core::convert::From<super::TemplateRequest<'raw>> for TemplateRequest {
+        fn from(value: super::TemplateRequest) -> Self {
+            Self {
+                name: value.name.into(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for TemplateRequest {
+        const MIN_SERIALIZED_SIZE: usize = <::std::string::String>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.name.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.name._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((i, Self { name: v0 }))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for TemplateRequest {}
+
+    pub use super::TemplateRun;
 
     #[derive(Clone, Debug, PartialEq)]
-    pub struct DeltaGrid {
-        pub delta: ::std::vec::Vec<Pixel>,
+    pub struct Template {
+        pub name: ::std::string::String,
+        pub layer: u32,
+        pub x: u32,
+        pub y: u32,
+        pub width: u32,
+        pub runs: ::std::vec::Vec<TemplateRun>,
     }
 
-    impl<'raw> ::core::convert::From<super::DeltaGrid<'raw>> for DeltaGrid {
-        fn from(value: super::DeltaGrid) -> Self {
+    impl<'raw> ::core::convert::From<super::Template<'raw>> for Template {
+        fn from(value: super::Template) -> Self {
             Self {
-                delta: value.delta.iter().map(|value| value).collect(),
+                name: value.name.into(),
+                layer: value.layer,
+                x: value.x,
+                y: value.y,
+                width: value.width,
+                runs: value.runs.iter().map(|value| value).collect(),
             }
         }
     }
 
-    impl<'raw> ::bebop::SubRecord<'raw> for DeltaGrid {
-        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<Pixel>>::MIN_SERIALIZED_SIZE;
+    impl<'raw> ::bebop::SubRecord<'raw> for Template {
+        const MIN_SERIALIZED_SIZE: usize = <::std::string::String>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <::std::vec::Vec<TemplateRun>>::MIN_SERIALIZED_SIZE;
 
         #[inline]
         fn serialized_size(&self) -> usize {
-            self.delta.serialized_size()
+            self.name.serialized_size()
+                + self.layer.serialized_size()
+                + self.x.serialized_size()
+                + self.y.serialized_size()
+                + self.width.serialized_size()
+                + self.runs.serialized_size()
         }
 
         ::bebop::define_serialize_chained!(Self => |zelf, dest| {
             Ok(
-                zelf.delta._serialize_chained(dest)?
+                zelf.name._serialize_chained(dest)? +
+                zelf.layer._serialize_chained(dest)? +
+                zelf.x._serialize_chained(dest)? +
+                zelf.y._serialize_chained(dest)? +
+                zelf.width._serialize_chained(dest)? +
+                zelf.runs._serialize_chained(dest)?
             )
         });
 
@@ -485,10 +2928,277 @@ pub mod owned {
 
             let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
             i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v3) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v4) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v5) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
 
-            Ok((i, Self { delta: v0 }))
+            Ok((
+                i,
+                Self {
+                    name: v0,
+                    layer: v1,
+                    x: v2,
+                    y: v3,
+                    width: v4,
+                    runs: v5,
+                },
+            ))
         }
     }
 
-    impl<'raw> ::bebop::Record<'raw> for DeltaGrid {}
+    impl<'raw> ::bebop::Record<'raw> for Template {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct TemplateUpload {
+        pub request_id: u32,
+        pub canvas: ::std::string::String,
+        pub template: Template,
+    }
+
+    impl<'raw> ::core::convert::From<super::TemplateUpload<'raw>> for TemplateUpload {
+        fn from(value: super::TemplateUpload) -> Self {
+            Self {
+                request_id: value.request_id,
+                canvas: value.canvas.into(),
+                template: value.template.into(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for TemplateUpload {
+        const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+            + <::std::string::String>::MIN_SERIALIZED_SIZE
+            + <Template>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.request_id.serialized_size()
+                + self.canvas.serialized_size()
+                + self.template.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.request_id._serialize_chained(dest)? +
+                zelf.canvas._serialize_chained(dest)? +
+                zelf.template._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    request_id: v0,
+                    canvas: v1,
+                    template: v2,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for TemplateUpload {}
+
+    pub use super::UndoPlacement;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct PixelPlaced {
+        pub layer: u32,
+        pub pixel: Pixel,
+        pub placed_at: u64,
+        pub placer: ::std::string::String,
+    }
+
+    impl<'raw> ::core::convert::From<super::PixelPlaced<'raw>> for PixelPlaced {
+        fn from(value: super::PixelPlaced) -> Self {
+            Self {
+                layer: value.layer,
+                pixel: value.pixel,
+                placed_at: value.placed_at,
+                placer: value.placer.into(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for PixelPlaced {
+        const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+            + <Pixel>::MIN_SERIALIZED_SIZE
+            + <u64>::MIN_SERIALIZED_SIZE
+            + <::std::string::String>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.layer.serialized_size()
+                + self.pixel.serialized_size()
+                + self.placed_at.serialized_size()
+                + self.placer.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.layer._serialize_chained(dest)? +
+                zelf.pixel._serialize_chained(dest)? +
+                zelf.placed_at._serialize_chained(dest)? +
+                zelf.placer._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v3) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    layer: v0,
+                    pixel: v1,
+                    placed_at: v2,
+                    placer: v3,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for PixelPlaced {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ClientHello {
+        pub versions: ::std::vec::Vec<u32>,
+    }
+
+    impl<'raw> ::core::convert::From<super::ClientHello<'raw>> for ClientHello {
+        fn from(value: super::ClientHello) -> Self {
+            Self {
+                versions: value.versions.iter().map(|value| value).collect(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for ClientHello {
+        const MIN_SERIALIZED_SIZE: usize = <::std::vec::Vec<u32>>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.versions.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.versions._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((i, Self { versions: v0 }))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for ClientHello {}
+
+    pub use super::ServerHello;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ServerNotice {
+        pub code: u32,
+        pub severity: u32,
+        pub text: ::std::string::String,
+    }
+
+    impl<'raw> ::core::convert::From<super::ServerNotice<'raw>> for ServerNotice {
+        fn from(value: super::ServerNotice) -> Self {
+            Self {
+                code: value.code,
+                severity: value.severity,
+                text: value.text.into(),
+            }
+        }
+    }
+
+    impl<'raw> ::bebop::SubRecord<'raw> for ServerNotice {
+        const MIN_SERIALIZED_SIZE: usize = <u32>::MIN_SERIALIZED_SIZE
+            + <u32>::MIN_SERIALIZED_SIZE
+            + <::std::string::String>::MIN_SERIALIZED_SIZE;
+
+        #[inline]
+        fn serialized_size(&self) -> usize {
+            self.code.serialized_size()
+                + self.severity.serialized_size()
+                + self.text.serialized_size()
+        }
+
+        ::bebop::define_serialize_chained!(Self => |zelf, dest| {
+            Ok(
+                zelf.code._serialize_chained(dest)? +
+                zelf.severity._serialize_chained(dest)? +
+                zelf.text._serialize_chained(dest)?
+            )
+        });
+
+        fn _deserialize_chained(raw: &'raw [u8]) -> ::bebop::DeResult<(usize, Self)> {
+            let mut i = 0;
+            if raw.len() - i < Self::MIN_SERIALIZED_SIZE {
+                let missing = Self::MIN_SERIALIZED_SIZE - (raw.len() - i);
+                return Err(::bebop::DeserializeError::MoreDataExpected(missing));
+            }
+
+            let (read, v0) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v1) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+            let (read, v2) = ::bebop::SubRecord::_deserialize_chained(&raw[i..])?;
+            i += read;
+
+            Ok((
+                i,
+                Self {
+                    code: v0,
+                    severity: v1,
+                    text: v2,
+                },
+            ))
+        }
+    }
+
+    impl<'raw> ::bebop::Record<'raw> for ServerNotice {}
 }
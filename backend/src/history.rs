@@ -0,0 +1,362 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    net::IpAddr,
+};
+
+use crate::{
+    generated::grid::Color,
+    moderation::BanTarget,
+    place::{LayerPixel, Region},
+    session::SessionId,
+};
+
+/// A placement applied to the grid, with what it painted over so it can be undone.
+#[derive(Clone, Copy, Debug)]
+pub struct PlacementRecord {
+    pub layer: u32,
+    pub x: u32,
+    pub y: u32,
+    /// What it painted, `None` when it made a pixel of a layer over the grid transparent.
+    pub color: Option<Color>,
+    /// `None` when it covered a transparent pixel of a layer over the grid.
+    pub previous: Option<Color>,
+    pub session: SessionId,
+    pub ip: IpAddr,
+    /// Unix time in milliseconds.
+    pub placed_at: u64,
+}
+
+/// Which placements a rollback reverts, every given condition has to match.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RollbackFilter {
+    pub placer: Option<BanTarget>,
+    /// Unix time in milliseconds.
+    pub since: Option<u64>,
+    /// Unix time in milliseconds.
+    pub until: Option<u64>,
+    pub region: Option<Region>,
+}
+
+impl RollbackFilter {
+    /// Whether the filter has no condition and would match every placement.
+    pub fn is_empty(&self) -> bool {
+        self.placer.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.region.is_none()
+    }
+
+    fn matches(&self, record: &PlacementRecord) -> bool {
+        self.placer
+            .is_none_or(|placer| placer.matches(record.session, record.ip))
+            && self.since.is_none_or(|since| record.placed_at >= since)
+            && self.until.is_none_or(|until| record.placed_at <= until)
+            && self
+                .region
+                .is_none_or(|region| region.contains(record.x, record.y))
+    }
+}
+
+/// The most recent placements, oldest first, up to a fixed number of them.
+pub struct History {
+    records: VecDeque<PlacementRecord>,
+    limit: usize,
+}
+
+impl History {
+    pub fn new(limit: usize) -> Self {
+        History {
+            records: VecDeque::new(),
+            limit,
+        }
+    }
+
+    pub fn record(&mut self, record: PlacementRecord) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.records.len() == self.limit {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Forgets the placements matching the filter and returns the pixels to paint back.
+    ///
+    /// Walks from the newest placement to the oldest. A pixel someone else painted over
+    /// afterwards keeps its current color, the rollback only restores what the matching
+    /// placements hid, which is the color before the oldest of them at the top of the pixel.
+    pub fn rollback(&mut self, filter: &RollbackFilter) -> Vec<LayerPixel> {
+        let mut painted_over = HashSet::new();
+        let mut restored = HashMap::new();
+        let mut reverted = vec![false; self.records.len()];
+
+        for (index, record) in self.records.iter().enumerate().rev() {
+            let position = (record.layer, record.x, record.y);
+            if painted_over.contains(&position) {
+                continue;
+            }
+            if filter.matches(record) {
+                restored.insert(position, record.previous);
+                reverted[index] = true;
+            } else {
+                painted_over.insert(position);
+            }
+        }
+
+        // The remaining history stays consistent: every kept placement's color is still
+        // what the next one on its pixel painted over.
+        let mut reverted = reverted.into_iter();
+        self.records
+            .retain(|_| !reverted.next().unwrap_or_default());
+
+        restored
+            .into_iter()
+            .map(|((layer, x, y), color)| LayerPixel { layer, x, y, color })
+            .collect()
+    }
+
+    /// The session's most recent placement, unless a later placement painted over it.
+    pub fn last_placement(&self, session: SessionId) -> Option<PlacementRecord> {
+        let index = self
+            .records
+            .iter()
+            .rposition(|record| record.session == session)?;
+        let record = self.records[index];
+        let painted_over = self
+            .records
+            .range(index + 1..)
+            .any(|later| (later.layer, later.x, later.y) == (record.layer, record.x, record.y));
+        (!painted_over).then_some(record)
+    }
+
+    /// Forgets the session's most recent placement once it was undone, the one before
+    /// becomes its last.
+    pub fn remove_last_placement(&mut self, session: SessionId) {
+        if let Some(index) = self
+            .records
+            .iter()
+            .rposition(|record| record.session == session)
+        {
+            self.records.remove(index);
+        }
+    }
+
+    /// Forgets the placements on a removed layer, those on the layers above it move down
+    /// with them.
+    pub fn remove_layer(&mut self, layer: u32) {
+        self.records.retain(|record| record.layer != layer);
+        for record in self.records.iter_mut() {
+            if record.layer > layer {
+                record.layer -= 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn color(value: u8) -> Option<Color> {
+        Some(Color::new(value, value, value))
+    }
+
+    struct Placer {
+        session: SessionId,
+        ip: IpAddr,
+    }
+
+    impl Placer {
+        fn new(last_octet: u8) -> Self {
+            Placer {
+                session: SessionId::random(),
+                ip: Ipv4Addr::new(10, 0, 0, last_octet).into(),
+            }
+        }
+
+        fn target(&self) -> Option<BanTarget> {
+            Some(BanTarget::Session(self.session))
+        }
+    }
+
+    /// Records a placement of the grid, painting over what the history says is there.
+    fn place(history: &mut History, placer: &Placer, x: u32, y: u32, paint: u8, at: u64) {
+        let previous = history
+            .records
+            .iter()
+            .rev()
+            .find(|record| (record.layer, record.x, record.y) == (0, x, y))
+            .map_or(color(255), |record| record.color);
+        history.record(PlacementRecord {
+            layer: 0,
+            x,
+            y,
+            color: color(paint),
+            previous,
+            session: placer.session,
+            ip: placer.ip,
+            placed_at: at,
+        });
+    }
+
+    fn sorted(mut pixels: Vec<LayerPixel>) -> Vec<(u32, u32, Option<Color>)> {
+        pixels.sort_by_key(|pixel| (pixel.x, pixel.y));
+        pixels
+            .into_iter()
+            .map(|pixel| (pixel.x, pixel.y, pixel.color))
+            .collect()
+    }
+
+    #[test]
+    fn rolls_back_a_placer_to_before_their_oldest_placement() {
+        let (vandal, player) = (Placer::new(1), Placer::new(2));
+        let mut history = History::new(100);
+        place(&mut history, &player, 0, 0, 1, 10);
+        place(&mut history, &vandal, 0, 0, 2, 11);
+        place(&mut history, &vandal, 0, 0, 3, 12);
+        place(&mut history, &vandal, 1, 0, 4, 13);
+
+        let filter = RollbackFilter {
+            placer: vandal.target(),
+            ..RollbackFilter::default()
+        };
+        let restored = sorted(history.rollback(&filter));
+        assert_eq!(restored, vec![(0, 0, color(1)), (1, 0, color(255))]);
+    }
+
+    #[test]
+    fn keeps_pixels_painted_over_by_someone_else() {
+        let (vandal, player) = (Placer::new(1), Placer::new(2));
+        let mut history = History::new(100);
+        place(&mut history, &vandal, 0, 0, 2, 10);
+        place(&mut history, &player, 0, 0, 1, 11);
+        place(&mut history, &vandal, 1, 0, 2, 12);
+
+        let filter = RollbackFilter {
+            placer: vandal.target(),
+            ..RollbackFilter::default()
+        };
+        let restored = sorted(history.rollback(&filter));
+        assert_eq!(restored, vec![(1, 0, color(255))]);
+        // The placement that was painted over stays, nothing of it is visible anyway.
+        assert_eq!(history.records.len(), 2);
+    }
+
+    #[test]
+    fn matches_placers_by_network() {
+        let (vandal, player) = (Placer::new(1), Placer::new(2));
+        let mut history = History::new(100);
+        place(&mut history, &vandal, 0, 0, 2, 10);
+        place(&mut history, &player, 1, 0, 1, 11);
+
+        let filter = RollbackFilter {
+            placer: Some("10.0.0.1".parse().unwrap()),
+            ..RollbackFilter::default()
+        };
+        assert_eq!(sorted(history.rollback(&filter)), vec![(0, 0, color(255))]);
+    }
+
+    #[test]
+    fn only_rolls_back_inside_the_region() {
+        let vandal = Placer::new(1);
+        let mut history = History::new(100);
+        place(&mut history, &vandal, 0, 0, 2, 10);
+        place(&mut history, &vandal, 5, 5, 2, 11);
+        place(&mut history, &vandal, 9, 9, 2, 12);
+
+        let filter = RollbackFilter {
+            region: Some(Region {
+                x: 4,
+                y: 4,
+                width: 5,
+                height: 5,
+            }),
+            ..RollbackFilter::default()
+        };
+        assert_eq!(sorted(history.rollback(&filter)), vec![(5, 5, color(255))]);
+        assert_eq!(history.records.len(), 2);
+    }
+
+    #[test]
+    fn only_rolls_back_inside_the_time_window() {
+        let player = Placer::new(1);
+        let mut history = History::new(100);
+        place(&mut history, &player, 0, 0, 1, 10);
+        place(&mut history, &player, 0, 0, 2, 20);
+        place(&mut history, &player, 0, 0, 3, 30);
+        place(&mut history, &player, 1, 0, 4, 40);
+
+        let filter = RollbackFilter {
+            since: Some(15),
+            until: Some(35),
+            ..RollbackFilter::default()
+        };
+        // The newest placement of 0,0 is inside the window, it goes back to the color before
+        // the oldest one inside it.
+        assert_eq!(sorted(history.rollback(&filter)), vec![(0, 0, color(1))]);
+    }
+
+    #[test]
+    fn a_later_placement_outside_the_window_blocks_the_pixel() {
+        let player = Placer::new(1);
+        let mut history = History::new(100);
+        place(&mut history, &player, 0, 0, 1, 10);
+        place(&mut history, &player, 0, 0, 2, 20);
+
+        let filter = RollbackFilter {
+            until: Some(15),
+            ..RollbackFilter::default()
+        };
+        assert!(history.rollback(&filter).is_empty());
+        assert_eq!(history.records.len(), 2);
+    }
+
+    #[test]
+    fn the_kept_history_stays_consistent() {
+        let (vandal, player) = (Placer::new(1), Placer::new(2));
+        let mut history = History::new(100);
+        place(&mut history, &player, 0, 0, 1, 10);
+        place(&mut history, &vandal, 0, 0, 2, 11);
+        place(&mut history, &player, 1, 0, 1, 12);
+        place(&mut history, &vandal, 1, 0, 2, 13);
+
+        let filter = RollbackFilter {
+            placer: vandal.target(),
+            ..RollbackFilter::default()
+        };
+        history.rollback(&filter);
+        assert!(history
+            .records
+            .iter()
+            .all(|record| record.session == player.session));
+        // Rolling back again finds nothing left to revert.
+        assert!(history.rollback(&filter).is_empty());
+        // Nothing paints over the player's last placement anymore, it can be undone again.
+        let last = history.last_placement(player.session).unwrap();
+        assert_eq!((last.x, last.y, last.color), (1, 0, color(1)));
+
+        let filter = RollbackFilter {
+            placer: player.target(),
+            ..RollbackFilter::default()
+        };
+        assert_eq!(
+            sorted(history.rollback(&filter)),
+            vec![(0, 0, color(255)), (1, 0, color(255))]
+        );
+        assert!(history.records.is_empty());
+    }
+
+    #[test]
+    fn forgets_the_oldest_placements_past_the_limit() {
+        let player = Placer::new(1);
+        let mut history = History::new(2);
+        place(&mut history, &player, 0, 0, 1, 10);
+        place(&mut history, &player, 1, 0, 1, 11);
+        place(&mut history, &player, 2, 0, 1, 12);
+        let kept: Vec<u32> = history.records.iter().map(|record| record.x).collect();
+        assert_eq!(kept, vec![1, 2]);
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time,
+};
+
+/// How long a refused client has to take its response before the socket is dropped.
+const REFUSAL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Caps how many connections the server holds at once, in total and from a single address,
+/// so a flood of sockets can't exhaust its tasks and memory. Zero lifts a cap.
+pub struct ConnectionLimits {
+    max_total: usize,
+    max_per_ip: usize,
+    open: Mutex<OpenConnections>,
+}
+
+#[derive(Default)]
+struct OpenConnections {
+    total: usize,
+    per_ip: HashMap<IpAddr, usize>,
+}
+
+/// Why a connection was turned away.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitExceeded {
+    ServerFull,
+    TooManyFromIp,
+}
+
+impl LimitExceeded {
+    /// Label of the refusal in the metrics.
+    pub fn reason(self) -> &'static str {
+        match self {
+            LimitExceeded::ServerFull => "server_full",
+            LimitExceeded::TooManyFromIp => "too_many_from_ip",
+        }
+    }
+
+    /// HTTP response sent in place of the WebSocket upgrade, without parsing the request.
+    fn response(self) -> &'static [u8] {
+        match self {
+            LimitExceeded::ServerFull => {
+                b"HTTP/1.1 503 Service Unavailable\r\nContent-Type: text/plain\r\nContent-Length: 47\r\nConnection: close\r\n\r\nThe canvas is full right now, try again later.\n"
+            }
+            LimitExceeded::TooManyFromIp => {
+                b"HTTP/1.1 429 Too Many Requests\r\nContent-Type: text/plain\r\nContent-Length: 52\r\nConnection: close\r\n\r\nToo many connections from your address, close some.\n"
+            }
+        }
+    }
+}
+
+impl ConnectionLimits {
+    pub fn new(max_total: usize, max_per_ip: usize) -> Arc<Self> {
+        Arc::new(ConnectionLimits {
+            max_total,
+            max_per_ip,
+            open: Mutex::default(),
+        })
+    }
+
+    /// Takes a slot for a connection from `ip`, which is given back when the slot is dropped.
+    pub fn acquire(self: &Arc<Self>, ip: IpAddr) -> Result<ConnectionSlot, LimitExceeded> {
+        let mut open = self.open.lock().unwrap();
+        if self.max_total != 0 && open.total >= self.max_total {
+            return Err(LimitExceeded::ServerFull);
+        }
+        let from_ip = open.per_ip.entry(ip).or_default();
+        if self.max_per_ip != 0 && *from_ip >= self.max_per_ip {
+            return Err(LimitExceeded::TooManyFromIp);
+        }
+        *from_ip += 1;
+        open.total += 1;
+        Ok(ConnectionSlot {
+            limits: self.clone(),
+            ip,
+        })
+    }
+}
+
+/// A connection counted against the limits for as long as it's held.
+pub struct ConnectionSlot {
+    limits: Arc<ConnectionLimits>,
+    ip: IpAddr,
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        let mut open = self.limits.open.lock().unwrap();
+        open.total -= 1;
+        if let Some(from_ip) = open.per_ip.get_mut(&self.ip) {
+            *from_ip -= 1;
+            if *from_ip == 0 {
+                open.per_ip.remove(&self.ip);
+            }
+        }
+    }
+}
+
+/// Counts the frames a client sends within each second.
+pub struct FrameRate {
+    max_per_second: u32,
+    window_start: Instant,
+    frames: u32,
+}
+
+impl FrameRate {
+    /// Zero lets any number of frames through.
+    pub fn new(max_per_second: u32) -> Self {
+        FrameRate {
+            max_per_second,
+            window_start: Instant::now(),
+            frames: 0,
+        }
+    }
+
+    /// Counts a received frame, `false` once the client went over the limit this second.
+    pub fn allow(&mut self) -> bool {
+        if self.max_per_second == 0 {
+            return true;
+        }
+        let now = Instant::now();
+        if now.duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.window_start = now;
+            self.frames = 0;
+        }
+        self.frames += 1;
+        self.frames <= self.max_per_second
+    }
+}
+
+/// Answers a connection over the limits with an HTTP error instead of upgrading it.
+pub async fn refuse(mut stream: TcpStream, exceeded: LimitExceeded) {
+    let _ = time::timeout(REFUSAL_TIMEOUT, async {
+        stream.write_all(exceeded.response()).await?;
+        stream.shutdown().await?;
+        // Closing with the request still unread would reset the connection, and the client
+        // might never see the response.
+        let mut buf = [0; 1024];
+        while stream.read(&mut buf).await? != 0 {}
+        Ok::<_, io::Error>(())
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 1));
+    const BOB: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 2));
+
+    #[test]
+    fn caps_connections_from_one_address() {
+        let limits = ConnectionLimits::new(0, 2);
+        let _first = limits.acquire(ALICE).unwrap();
+        let _second = limits.acquire(ALICE).unwrap();
+        assert_eq!(
+            limits.acquire(ALICE).err(),
+            Some(LimitExceeded::TooManyFromIp)
+        );
+        assert!(limits.acquire(BOB).is_ok());
+    }
+
+    #[test]
+    fn caps_connections_in_total() {
+        let limits = ConnectionLimits::new(2, 0);
+        let _alice = limits.acquire(ALICE).unwrap();
+        let _bob = limits.acquire(BOB).unwrap();
+        assert_eq!(limits.acquire(BOB).err(), Some(LimitExceeded::ServerFull));
+    }
+
+    #[test]
+    fn zero_lifts_the_caps() {
+        let limits = ConnectionLimits::new(0, 0);
+        let slots: Vec<ConnectionSlot> = (0..100).map(|_| limits.acquire(ALICE).unwrap()).collect();
+        assert_eq!(slots.len(), 100);
+    }
+
+    #[test]
+    fn dropped_slots_are_given_back() {
+        let limits = ConnectionLimits::new(2, 1);
+        let alice = limits.acquire(ALICE).unwrap();
+        let bob = limits.acquire(BOB).unwrap();
+        assert!(limits.acquire(ALICE).is_err());
+        drop(alice);
+        let _alice = limits.acquire(ALICE).unwrap();
+        drop(bob);
+        assert!(!limits.open.lock().unwrap().per_ip.contains_key(&BOB));
+        assert_eq!(limits.open.lock().unwrap().total, 1);
+    }
+
+    #[test]
+    fn limits_frames_within_a_second() {
+        let mut rate = FrameRate::new(3);
+        assert!((0..3).all(|_| rate.allow()));
+        assert!(!rate.allow());
+        assert!(!rate.allow());
+    }
+
+    #[test]
+    fn counts_frames_again_the_next_second() {
+        let mut rate = FrameRate::new(1);
+        assert!(rate.allow());
+        assert!(!rate.allow());
+        rate.window_start -= Duration::from_secs(1);
+        assert!(rate.allow());
+        assert!(!rate.allow());
+    }
+
+    #[test]
+    fn zero_lets_every_frame_through() {
+        let mut rate = FrameRate::new(0);
+        assert!((0..1000).all(|_| rate.allow()));
+    }
+}
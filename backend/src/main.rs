@@ -1,142 +1,187 @@
 use std::{
-    env,
+    fs,
     io::Error,
-    sync::Arc,
-    time::{Duration, Instant},
-};
-
-use bebop::prelude::*;
-use futures_util::{future, pin_mut, SinkExt, StreamExt, TryStreamExt};
-use generated::grid::*;
-
-use local_ip_address::local_ip;
-use tokio::{
-    net::{TcpListener, TcpStream},
     sync::{
-        broadcast::{self},
-        mpsc, RwLock,
+        atomic::{AtomicU64, Ordering},
+        Arc,
     },
+    time::Duration,
 };
-use tokio_tungstenite::tungstenite::Message;
 
+use tokio::{net::TcpListener, signal};
+use tracing::{debug, info, info_span, warn, Instrument};
+use tracing_subscriber::EnvFilter;
+
+mod admin;
+mod auth;
+mod canvas;
+mod config;
+mod connection;
+mod debug_protocol;
 mod generated;
+mod history;
+mod limits;
+mod manipulator;
+mod metrics;
+mod moderation;
 mod place;
+mod protocol;
+mod regions;
+mod session;
+mod storage;
+mod templates;
+
+/// How long clients have to receive the shutdown notice before the process exits.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(1);
+
+/// How long to wait before accepting again after it failed, e.g. when the process ran out of
+/// file descriptors and has to wait for connections to close.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    let my_local_ip = local_ip().unwrap();
-    let addr = match env::args().nth(1) {
-        Some(arg) if arg == "--host".to_string() => {
-            my_local_ip.to_string() + ":8080"
-        }
-        Some(_) | None => "127.0.0.1:8080".to_string(),
-    };
+    // Log level and per-module filters come from `RUST_LOG`, e.g. `RUST_LOG=backend=debug`.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let config = Arc::new(config::Config::load());
+    let metrics = Arc::new(metrics::Metrics::new());
 
     // Create the event loop and TCP listener we'll accept connections on.
-    let try_socket = TcpListener::bind(&addr).await;
+    let try_socket = TcpListener::bind(&config.addr).await;
     let listener = try_socket.expect("Failed to bind");
-    println!("Listening on: {}", addr);
+    info!(addr = %config.addr, "listening");
 
-    let shared_state: Arc<RwLock<place::State>> = Arc::new(RwLock::new(place::State::new()));
-    {
-        let now = Instant::now();
-        let mut state_guard = shared_state.write().await;
-        let state = &mut *state_guard;
-        state.set_grid_size(100, 100).await;
-        state.set_new_encoded_grid_data().await;
-        println!("{:?}", now.elapsed());
+    let metrics_listener = TcpListener::bind(&config.metrics_addr)
+        .await
+        .expect("Failed to bind the metrics address");
+    tokio::spawn(metrics::serve(metrics_listener, metrics.clone()));
+
+    fs::create_dir_all(&config.data_dir).expect("Failed to create the data directory");
+    let bans = moderation::BanList::load(config.data_dir.join("bans.json"))
+        .expect("Failed to load the ban list");
+    let bans = Arc::new(std::sync::RwLock::new(bans));
+    let canvases = canvas::Canvases::load(config.clone(), bans.clone(), metrics.clone())
+        .await
+        .expect("Failed to load the canvases");
+
+    if config.starts_at.is_some() || config.ends_at.is_some() {
+        let default_canvas = canvases
+            .get(canvas::DEFAULT_CANVAS)
+            .expect("PLACE_STARTS_AT and PLACE_ENDS_AT need the default canvas");
+        let saved = default_canvas.state.read().await.get_schedule();
+        // A schedule a moderator set at runtime outlives the environment it started with.
+        if saved.starts_at.is_some() || saved.ends_at.is_some() {
+            info!("the default canvas keeps the schedule it was saved with");
+        } else {
+            let schedule = admin::CanvasCommand::Schedule {
+                starts_at: config.starts_at,
+                ends_at: config.ends_at,
+            };
+            match admin::execute_on(&default_canvas, schedule).await {
+                Ok(message) => info!("scheduled the default canvas, {}", message),
+                Err(message) => warn!("could not schedule the default canvas: {}", message),
+            }
+        }
     }
 
-    let (sender_grid_manipulator, receiver_grid_manipulator) = mpsc::channel::<Vec<u8>>(16);
-    let (sender_clients, _receiver_clients) = broadcast::channel::<Vec<u8>>(16);
-
-    tokio::spawn(grid_manipulator(
-        shared_state.clone(),
-        receiver_grid_manipulator,
-        sender_clients.clone(),
-    ));
-
-    tokio::spawn(full_grid_saver(shared_state.clone()));
+    let session_keys = match &config.session_secret {
+        Some(secret) => session::SessionKeys::new(secret.as_bytes()),
+        None => {
+            warn!("PLACE_SESSION_SECRET is not set, sessions won't survive a restart");
+            session::SessionKeys::new(&rand::random::<[u8; 32]>())
+        }
+    };
 
-    while let Ok((stream, _)) = listener.accept().await {
-        tokio::spawn(accept_connection(
-            stream,
-            shared_state.clone(),
-            sender_grid_manipulator.clone(),
-            sender_clients.subscribe(),
-        ));
+    let verifier = config.jwks_path.as_ref().map(|path| {
+        let verifier =
+            auth::JwksVerifier::load(path, config.jwt_issuer.clone(), config.jwt_audience.clone())
+                .expect("Failed to load the JWKS file");
+        info!(path = %path, "placements require authentication");
+        Arc::new(verifier) as Arc<dyn auth::Verifier>
+    });
+
+    let context = connection::Context {
+        session_keys: Arc::new(session_keys),
+        verifier,
+        bans,
+        config,
+        canvases: Arc::new(canvases),
+        metrics,
+    };
+    tokio::spawn(admin::console(context.clone()).instrument(info_span!("console")));
+    let limits = limits::ConnectionLimits::new(
+        context.config.max_connections,
+        context.config.max_connections_per_ip,
+    );
+    let next_connection_id = AtomicU64::new(0);
+
+    let accept_connections = async {
+        loop {
+            let (stream, addr) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    warn!(error = %err, "could not accept a connection");
+                    context.metrics.accept_errors.inc();
+                    tokio::time::sleep(ACCEPT_BACKOFF).await;
+                    continue;
+                }
+            };
+            let slot = match limits.acquire(addr.ip()) {
+                Ok(slot) => slot,
+                Err(exceeded) => {
+                    debug!(%addr, reason = exceeded.reason(), "refusing connection");
+                    context
+                        .metrics
+                        .refused_connections
+                        .with_label_values(&[exceeded.reason()])
+                        .inc();
+                    tokio::spawn(limits::refuse(stream, exceeded));
+                    continue;
+                }
+            };
+            let connection = connection::handle_connection(
+                stream,
+                next_connection_id.fetch_add(1, Ordering::Relaxed),
+                context.clone(),
+            );
+            tokio::spawn(async move {
+                connection.await;
+                drop(slot);
+            });
+        }
+    };
+    tokio::select! {
+        () = accept_connections => {}
+        () = shutdown_signal() => {
+            info!("shutting down");
+            let notice = protocol::encode_notice(
+                generated::grid::NOTICE_SHUTDOWN,
+                generated::grid::NOTICE_ERROR,
+                "The server is restarting, the canvas will be back in a moment.",
+            );
+            context.canvases.broadcast(notice);
+            tokio::time::sleep(SHUTDOWN_GRACE).await;
+        }
     }
 
     Ok(())
 }
 
-async fn accept_connection<'a>(
-    stream: TcpStream,
-    state: Arc<RwLock<place::State>>,
-    sender_grid_manipulator: mpsc::Sender<Vec<u8>>,
-    mut receiver_clients: broadcast::Receiver<Vec<u8>>,
-) {
-    let addr = stream
-        .peer_addr()
-        .expect("connected streams should have a peer address");
-    println!("Peer address: {}", addr);
-
-    let ws_stream = tokio_tungstenite::accept_async(stream)
-        .await
-        .expect("Error during the websocket handshake occurred");
-
-    let (mut write, read) = ws_stream.split();
-    write
-        .feed(Message::Binary(
-            state.read().await.get_encoded_grid_data_cloned().await,
-        ))
-        .await;
-    write
-        .feed(Message::Binary(
-            state.read().await.get_encoded_delta_data_cloned().await,
-        ))
-        .await;
-    write.flush().await;
-
-    let receive_future = read
-        .try_filter(|msg| future::ready(msg.is_binary()))
-        .try_for_each(|msg| {
-            if let Err(_) = sender_grid_manipulator.try_send(msg.into_data()) {
-                return future::ok(());
-            }
-            future::ok(())
-        });
-
-    let send_future = async {
-        while let Ok(encoded_data) = receiver_clients.recv().await {
-            write.send(Message::Binary(encoded_data)).await;
+/// Resolves on Ctrl+C, or when the process is asked to terminate on Unix.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate = signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM");
+        tokio::select! {
+            _ = signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
         }
-    };
-
-    pin_mut!(receive_future, send_future);
-    future::select(receive_future, send_future).await;
-}
-
-async fn grid_manipulator(
-    state: Arc<RwLock<place::State>>,
-    mut receiver_grid_manipulator: mpsc::Receiver<Vec<u8>>,
-    sender_clients: broadcast::Sender<Vec<u8>>,
-) {
-    while let Some(encoded_data) = receiver_grid_manipulator.recv().await {
-        let decoded_pixel =
-            Pixel::deserialize(&BebopData::deserialize(&encoded_data).unwrap().encoded_data)
-                .unwrap();
-        let state_guard = state.read().await;
-        state_guard.set_pixel(decoded_pixel).await;
-        state_guard.add_pixel_to_delta(decoded_pixel).await;
-        state_guard.set_new_encoded_delta_data().await;
-        sender_clients.send(encoded_data);
-    }
-}
-async fn full_grid_saver(state: Arc<RwLock<place::State>>) {
-    let mut tick = tokio::time::interval(Duration::from_millis(5000));
-    loop {
-        tick.tick().await;
-        state.read().await.set_new_encoded_grid_data().await;
     }
+    #[cfg(not(unix))]
+    let _ = signal::ctrl_c().await;
 }
@@ -0,0 +1,737 @@
+use std::{
+    collections::BTreeMap,
+    io,
+    net::IpAddr,
+    path::PathBuf,
+    sync::{self, Arc},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use bebop::SliceWrapper;
+use futures_util::future;
+use prometheus::IntGauge;
+use tokio::sync::{broadcast, mpsc, RwLock};
+use tracing::{debug, info};
+
+use crate::{
+    admin::CanvasCommand,
+    auth::Identity,
+    canvas::CanvasSettings,
+    generated::grid::*,
+    history::{History, PlacementRecord},
+    metrics::Metrics,
+    moderation::{unix_now, unix_now_millis, BanList},
+    place::{
+        Batch, BatchPlacement, CanvasStatus, Cooldowns, GridCommand, LayerPixel, Placement,
+        PlacementStatus, State, Undo,
+    },
+    protocol,
+    regions::{ProtectedRegion, ProtectedRegions},
+    session::SessionId,
+    templates::{self, Template},
+};
+
+/// How often clients are told how much of each template is left to paint, when it changed.
+const TEMPLATES_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Owns every change to the grid, applying the commands in the order they were queued
+/// and broadcasting the result to the clients.
+pub struct GridManipulator {
+    pub state: Arc<RwLock<State>>,
+    pub cooldowns: Cooldowns,
+    /// The canvas's own cooldown, saved with its settings, `None` following the default.
+    pub cooldown: Option<Duration>,
+    /// `PLACE_COOLDOWN_MS`.
+    pub default_cooldown: Duration,
+    pub bans: Arc<sync::RwLock<BanList>>,
+    pub protected_regions: ProtectedRegions,
+    pub history: History,
+    /// How long after placing a pixel its placer can still undo it.
+    pub undo_window: Duration,
+    pub sender_clients: broadcast::Sender<Vec<u8>>,
+    pub metrics: Arc<Metrics>,
+    /// Messages waiting in the queue of this canvas.
+    pub queue_depth: IntGauge,
+    /// Where the canvas settings are saved whenever a moderator changes them.
+    pub settings_path: PathBuf,
+    pub templates_path: PathBuf,
+}
+
+impl GridManipulator {
+    /// Applies commands until every sender is gone or the canvas is deleted.
+    pub async fn run(mut self, mut receiver: mpsc::Receiver<GridCommand>) {
+        let mut templates_tick = tokio::time::interval(TEMPLATES_INTERVAL);
+        let mut sent_templates = self.state.read().await.encode_templates();
+        loop {
+            let next_change = self
+                .state
+                .read()
+                .await
+                .get_schedule()
+                .next_change(unix_now());
+            let command = tokio::select! {
+                command = receiver.recv() => match command {
+                    Some(command) => command,
+                    None => break,
+                },
+                () = sleep_until(next_change) => {
+                    let schedule = self.state.read().await.get_schedule();
+                    info!(status = %schedule.status(unix_now()), "canvas status changed");
+                    self.broadcast_schedule().await;
+                    continue;
+                }
+                _ = templates_tick.tick() => {
+                    let encoded_templates = self.state.read().await.encode_templates();
+                    if encoded_templates != sent_templates {
+                        let _ = self.sender_clients.send(encoded_templates.clone());
+                        sent_templates = encoded_templates;
+                    }
+                    continue;
+                }
+            };
+            self.queue_depth.set(receiver.len() as i64);
+            match command {
+                GridCommand::Place(Placement {
+                    layer,
+                    pixel,
+                    session,
+                    identity,
+                    ip,
+                    placer,
+                    reply,
+                }) => {
+                    let status = self
+                        .place(
+                            layer,
+                            pixel,
+                            session,
+                            identity.as_deref(),
+                            ip,
+                            placer.as_deref(),
+                        )
+                        .await;
+                    // The client may have disconnected while its pixel was queued.
+                    let _ = reply.send(status);
+                }
+                GridCommand::PaintBatch(BatchPlacement {
+                    layer,
+                    batch,
+                    session,
+                    identity,
+                    ip,
+                    reply,
+                }) => {
+                    let status = self
+                        .paint_batch(layer, batch, session, identity.as_deref(), ip)
+                        .await;
+                    let _ = reply.send(status);
+                }
+                GridCommand::Undo(Undo { session, ip, reply }) => {
+                    let status = self.undo(session, ip).await;
+                    let _ = reply.send(status);
+                }
+                GridCommand::Admin { command, reply } => {
+                    let _ = reply.send(self.admin(command).await);
+                }
+                GridCommand::Stop => break,
+            }
+        }
+    }
+
+    async fn place(
+        &mut self,
+        layer: u32,
+        pixel: Pixel,
+        session: SessionId,
+        identity: Option<&Identity>,
+        ip: IpAddr,
+        placer: Option<&str>,
+    ) -> PlacementStatus {
+        debug!(
+            layer,
+            x = { pixel.x },
+            y = { pixel.y },
+            color = ?{ pixel.color },
+            session = %session,
+            "placing pixel"
+        );
+        let state_guard = self.state.read().await;
+        if let Err(status) = self.check_can_paint(&state_guard, session, ip) {
+            return status;
+        }
+        match state_guard.get_layers().get(layer as usize) {
+            Some(settings) if settings.restricted => {
+                return self.reject(PlacementStatus::LayerRestricted, "layer_restricted")
+            }
+            Some(_) => {}
+            None => return self.reject(PlacementStatus::OutOfBounds, "out_of_bounds"),
+        }
+        if !state_guard.allows_color(pixel.color) {
+            return self.reject(PlacementStatus::InvalidColor, "invalid_color");
+        }
+        let now = Instant::now();
+        if let Some(remaining) = self.cooldowns.remaining(session, now) {
+            return self.reject(PlacementStatus::RateLimited { remaining }, "rate_limited");
+        }
+        if !self
+            .protected_regions
+            .allows(pixel.x, pixel.y, session, identity)
+        {
+            return self.reject(PlacementStatus::Protected, "protected");
+        }
+        let layer_pixel = LayerPixel {
+            layer,
+            x: pixel.x,
+            y: pixel.y,
+            color: Some(pixel.color),
+        };
+        let previous = match state_guard.set_layer_pixel(layer_pixel).await {
+            Some(previous) => previous.color,
+            None => return self.reject(PlacementStatus::OutOfBounds, "out_of_bounds"),
+        };
+        self.cooldowns.record(session, now);
+        if layer == 0 {
+            state_guard.add_pixel_to_delta(pixel).await;
+            state_guard.set_new_encoded_delta_data().await;
+        }
+        drop(state_guard);
+
+        let placed_at = unix_now_millis();
+        self.history.record(PlacementRecord {
+            layer,
+            x: pixel.x,
+            y: pixel.y,
+            previous,
+            color: Some(pixel.color),
+            session,
+            ip,
+            placed_at,
+        });
+        self.metrics.placements.inc();
+        // Turned back into a `Pixel` or `LayerDelta` for clients that predate it.
+        let placed = PixelPlaced {
+            layer,
+            pixel,
+            placed_at,
+            placer: placer.unwrap_or_default(),
+        };
+        // Sending only fails when nobody is connected.
+        let _ = self
+            .sender_clients
+            .send(protocol::encode(PIXEL_PLACED_OPCODE, &placed));
+        PlacementStatus::Accepted
+    }
+
+    /// Paints the whole batch or, when any of its pixels would be refused, none of it.
+    async fn paint_batch(
+        &mut self,
+        layer: u32,
+        batch: Batch,
+        session: SessionId,
+        identity: Option<&Identity>,
+        ip: IpAddr,
+    ) -> PlacementStatus {
+        let state_guard = self.state.read().await;
+        if let Err(status) = self.check_can_paint(&state_guard, session, ip) {
+            return status;
+        }
+        match state_guard.get_layers().get(layer as usize) {
+            Some(settings) if settings.restricted => {
+                return self.reject(PlacementStatus::LayerRestricted, "layer_restricted")
+            }
+            Some(_) => {}
+            None => return self.reject(PlacementStatus::OutOfBounds, "out_of_bounds"),
+        }
+        let width = state_guard.get_grid_width() as u32;
+        let height = state_guard.get_grid_height() as u32;
+        if !batch.fits(width, height) {
+            return self.reject(PlacementStatus::OutOfBounds, "out_of_bounds");
+        }
+        let pixels = batch.into_pixels();
+        if !pixels
+            .iter()
+            .all(|pixel| state_guard.allows_color(pixel.color))
+        {
+            return self.reject(PlacementStatus::InvalidColor, "invalid_color");
+        }
+        drop(state_guard);
+        if !pixels.iter().all(|pixel| {
+            self.protected_regions
+                .allows(pixel.x, pixel.y, session, identity)
+        }) {
+            return self.reject(PlacementStatus::Protected, "protected");
+        }
+
+        let pixels: Vec<LayerPixel> = pixels
+            .into_iter()
+            .map(|pixel| LayerPixel {
+                layer,
+                x: pixel.x,
+                y: pixel.y,
+                color: Some(pixel.color),
+            })
+            .collect();
+        let painted = self.paint(pixels, Some((session, ip))).await;
+        debug!(layer, pixels = painted, session = %session, "painted batch");
+        PlacementStatus::Accepted
+    }
+
+    /// Restores what the session's last placement painted over, if it was placed within the
+    /// undo window and nobody painted the pixel since.
+    async fn undo(&mut self, session: SessionId, ip: IpAddr) -> PlacementStatus {
+        let state_guard = self.state.read().await;
+        if let Err(status) = self.check_can_paint(&state_guard, session, ip) {
+            return status;
+        }
+        let record = match self.history.last_placement(session) {
+            Some(record)
+                if u128::from(unix_now_millis().saturating_sub(record.placed_at))
+                    < self.undo_window.as_millis() =>
+            {
+                record
+            }
+            _ => return self.reject(PlacementStatus::NothingToUndo, "nothing_to_undo"),
+        };
+        // Moderators paint without leaving a placement in the history.
+        let current = state_guard
+            .get_layer_color(record.layer, record.x, record.y)
+            .await;
+        drop(state_guard);
+        if current != record.color {
+            return self.reject(PlacementStatus::NothingToUndo, "nothing_to_undo");
+        }
+
+        self.history.remove_last_placement(session);
+        let pixel = LayerPixel {
+            layer: record.layer,
+            x: record.x,
+            y: record.y,
+            color: record.previous,
+        };
+        self.paint(vec![pixel], None).await;
+        self.metrics.undone_placements.inc();
+        debug!(layer = record.layer, x = record.x, y = record.y, session = %session, "undid placement");
+        PlacementStatus::Accepted
+    }
+
+    /// Refuses banned and muted placers, and everyone while the canvas isn't open.
+    fn check_can_paint(
+        &self,
+        state: &State,
+        session: SessionId,
+        ip: IpAddr,
+    ) -> Result<(), PlacementStatus> {
+        let banned = self.bans.read().unwrap().lookup(session, ip).is_some();
+        if banned {
+            return Err(self.reject(PlacementStatus::Banned, "banned"));
+        }
+        match state.get_schedule().status(unix_now()) {
+            CanvasStatus::Open => Ok(()),
+            CanvasStatus::Locked => {
+                Err(self.reject(PlacementStatus::CanvasLocked, "canvas_locked"))
+            }
+            CanvasStatus::Ended => Err(self.reject(PlacementStatus::CanvasEnded, "canvas_ended")),
+        }
+    }
+
+    fn reject(&self, status: PlacementStatus, reason: &str) -> PlacementStatus {
+        self.metrics
+            .rejected_placements
+            .with_label_values(&[reason])
+            .inc();
+        status
+    }
+
+    /// Applies a moderator's command, describing the outcome or why it failed.
+    async fn admin(&mut self, command: CanvasCommand) -> Result<String, String> {
+        debug!(?command, "applying admin command");
+        match command {
+            CanvasCommand::Rollback(filter) => {
+                let pixels = self.history.rollback(&filter);
+                let restored = self.paint(pixels, None).await;
+                info!(pixels = restored, ?filter, "rolled back placements");
+                Ok(format!("rolled back {} pixels", restored))
+            }
+            CanvasCommand::Resize { width, height } => {
+                let mut state_guard = self.state.write().await;
+                state_guard
+                    .set_grid_size(width as usize, height as usize)
+                    .await;
+                state_guard.set_new_encoded_grid_data().await;
+                let encoded_grid_data = state_guard.get_encoded_grid_data_cloned().await;
+                let encoded_delta_data = state_guard.get_encoded_delta_data_cloned().await;
+                drop(state_guard);
+                // Clients replace their canvas once the grid is followed by its (empty) delta.
+                let _ = self.sender_clients.send(encoded_grid_data);
+                let _ = self.sender_clients.send(encoded_delta_data);
+                // Resizing the grid blanks the layers over it on the clients.
+                self.broadcast_layers().await;
+                self.saved(format!("resized the canvas to {}x{}", width, height))
+                    .await
+            }
+            CanvasCommand::Lock(locked) => {
+                let mut state_guard = self.state.write().await;
+                let mut schedule = state_guard.get_schedule();
+                schedule.locked = locked;
+                state_guard.set_schedule(schedule);
+                drop(state_guard);
+                self.broadcast_schedule().await;
+                self.saved(format!("the canvas is {}", schedule.status(unix_now())))
+                    .await
+            }
+            CanvasCommand::Schedule { starts_at, ends_at } => {
+                if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+                    if starts_at >= ends_at {
+                        return Err(format!(
+                            "the canvas would end at {} before starting at {}",
+                            ends_at, starts_at
+                        ));
+                    }
+                }
+                let mut state_guard = self.state.write().await;
+                let mut schedule = state_guard.get_schedule();
+                schedule.starts_at = starts_at;
+                schedule.ends_at = ends_at;
+                state_guard.set_schedule(schedule);
+                drop(state_guard);
+                self.broadcast_schedule().await;
+                self.saved(format!("the canvas is {}", schedule.status(unix_now())))
+                    .await
+            }
+            CanvasCommand::Clear { region, layer } => {
+                let state_guard = self.state.read().await;
+                let layer = find_layer(&state_guard, layer.as_deref())?;
+                let width = state_guard.get_grid_width() as u32;
+                let height = state_guard.get_grid_height() as u32;
+                drop(state_guard);
+                let rows = region.y..region.y.saturating_add(region.height).min(height);
+                let columns = region.x..region.x.saturating_add(region.width).min(width);
+                let pixels: Vec<LayerPixel> = rows
+                    .flat_map(|y| {
+                        columns.clone().map(move |x| LayerPixel {
+                            layer,
+                            x,
+                            y,
+                            color: None,
+                        })
+                    })
+                    .collect();
+                let cleared = self.paint(pixels, None).await;
+                Ok(format!("cleared {} pixels", cleared))
+            }
+            CanvasCommand::Paint { x, y, color, layer } => {
+                let layer = find_layer(&*self.state.read().await, layer.as_deref())?;
+                match self
+                    .paint(vec![LayerPixel { layer, x, y, color }], None)
+                    .await
+                {
+                    0 => Err(format!("{},{} is outside the canvas", x, y)),
+                    _ => Ok(format!("painted {},{}", x, y)),
+                }
+            }
+            CanvasCommand::AddLayer(settings) => {
+                let mut state_guard = self.state.write().await;
+                if state_guard.layer_index(&settings.name).is_some() {
+                    return Err(format!("layer {} already exists", settings.name));
+                }
+                let message = format!("added layer {}", settings);
+                state_guard.add_layer(settings);
+                drop(state_guard);
+                self.broadcast_layers().await;
+                self.saved(message).await
+            }
+            CanvasCommand::RemoveLayer(name) => {
+                let mut state_guard = self.state.write().await;
+                let layer = find_layer(&state_guard, Some(&name))?;
+                if layer == 0 {
+                    return Err(format!("{} is the grid itself and can't be removed", name));
+                }
+                let templates = state_guard.get_templates().len();
+                state_guard.remove_layer(layer);
+                let removed_templates = templates != state_guard.get_templates().len();
+                drop(state_guard);
+                self.history.remove_layer(layer);
+                self.broadcast_layers().await;
+                if removed_templates {
+                    self.broadcast_templates().await;
+                    if let Err(err) = self.save_templates().await {
+                        return Err(format!(
+                            "removed layer {} but could not save the templates: {}",
+                            name, err
+                        ));
+                    }
+                }
+                self.saved(format!("removed layer {}", name)).await
+            }
+            CanvasCommand::RestrictLayer { name, restricted } => {
+                let mut state_guard = self.state.write().await;
+                let layer = find_layer(&state_guard, Some(&name))?;
+                state_guard.set_layer_restricted(layer, restricted);
+                drop(state_guard);
+                self.broadcast_layers().await;
+                self.saved(match restricted {
+                    true => format!("only moderators can paint {}", name),
+                    false => format!("anyone can paint {}", name),
+                })
+                .await
+            }
+            CanvasCommand::AddTemplate(template) => {
+                let mut state_guard = self.state.write().await;
+                if template.layer as usize >= state_guard.get_layers().len() {
+                    return Err(format!("there's no layer {}", template.layer));
+                }
+                let name = template.name.clone();
+                state_guard.add_template(template).await;
+                let description = state_guard.find_template(&name).map(Template::to_string);
+                drop(state_guard);
+                self.templates_saved(format!("added template {}", description.unwrap_or(name)))
+                    .await
+            }
+            CanvasCommand::RemoveTemplate(name) => {
+                if !self.state.write().await.remove_template(&name) {
+                    return Err(format!("no template is named {}", name));
+                }
+                self.templates_saved(format!("removed template {}", name))
+                    .await
+            }
+            CanvasCommand::ListTemplates => {
+                let state_guard = self.state.read().await;
+                let lines: Vec<String> = state_guard
+                    .get_templates()
+                    .iter()
+                    .map(Template::to_string)
+                    .collect();
+                if lines.is_empty() {
+                    Ok("there are no templates".to_string())
+                } else {
+                    Ok(lines.join("\n"))
+                }
+            }
+            CanvasCommand::ListLayers => {
+                let state_guard = self.state.read().await;
+                let lines: Vec<String> = state_guard
+                    .get_layers()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, layer)| format!("{}: {}", index, layer))
+                    .collect();
+                Ok(lines.join("\n"))
+            }
+            CanvasCommand::Protect(protected) => {
+                let description = protected.to_string();
+                self.protected_regions.add(protected).map_err(|err| {
+                    format!(
+                        "protected {}, but the regions could not be saved: {}",
+                        description, err
+                    )
+                })?;
+                Ok(format!("protected {}", description))
+            }
+            CanvasCommand::Unprotect(name) => match self.protected_regions.remove(&name) {
+                Ok(true) => Ok(format!("{} is no longer protected", name)),
+                Ok(false) => Err(format!("no region is named {}", name)),
+                Err(err) => Err(format!(
+                    "lifted the protection but could not save the regions: {}",
+                    err
+                )),
+            },
+            CanvasCommand::ListRegions => {
+                let lines: Vec<String> = self
+                    .protected_regions
+                    .iter()
+                    .map(ProtectedRegion::to_string)
+                    .collect();
+                if lines.is_empty() {
+                    Ok("no region is protected".to_string())
+                } else {
+                    Ok(lines.join("\n"))
+                }
+            }
+            CanvasCommand::Cooldown(cooldown) => {
+                self.cooldown = cooldown;
+                let cooldown = cooldown.unwrap_or(self.default_cooldown);
+                self.cooldowns.set_cooldown(cooldown);
+                self.saved(match cooldown.is_zero() {
+                    true => "there is no cooldown".to_string(),
+                    false => format!("the cooldown is {:?}", cooldown),
+                })
+                .await
+            }
+            CanvasCommand::Palette(palette) => {
+                let mut state_guard = self.state.write().await;
+                state_guard.set_palette(palette);
+                let encoded_palette = state_guard.encode_palette();
+                let colors = state_guard.get_palette().len();
+                drop(state_guard);
+                let _ = self.sender_clients.send(encoded_palette);
+                self.saved(match colors {
+                    0 => "any color can be placed".to_string(),
+                    colors => format!("the palette has {} colors", colors),
+                })
+                .await
+            }
+        }
+    }
+
+    /// Saves the canvas settings after a change described by `message`.
+    async fn saved(&self, message: String) -> Result<String, String> {
+        match self.save_settings().await {
+            Ok(()) => Ok(message),
+            Err(err) => Err(format!(
+                "{}, but the canvas settings could not be saved: {}",
+                message, err
+            )),
+        }
+    }
+
+    async fn save_settings(&self) -> io::Result<()> {
+        let state_guard = self.state.read().await;
+        let settings = CanvasSettings {
+            width: state_guard.get_grid_width() as u32,
+            height: state_guard.get_grid_height() as u32,
+            palette: state_guard.get_palette().to_vec(),
+            schedule: state_guard.get_schedule(),
+            layers: state_guard.get_layers().to_vec(),
+            cooldown_ms: self.cooldown.map(|cooldown| cooldown.as_millis() as u64),
+        };
+        drop(state_guard);
+        settings.save(&self.settings_path)
+    }
+
+    /// Sends the templates to the clients and saves them after a change described by
+    /// `message`.
+    async fn templates_saved(&self, message: String) -> Result<String, String> {
+        self.broadcast_templates().await;
+        match self.save_templates().await {
+            Ok(()) => Ok(message),
+            Err(err) => Err(format!(
+                "{}, but the templates could not be saved: {}",
+                message, err
+            )),
+        }
+    }
+
+    async fn save_templates(&self) -> io::Result<()> {
+        let state_guard = self.state.read().await;
+        templates::save(&self.templates_path, state_guard.get_templates())
+    }
+
+    async fn broadcast_templates(&self) {
+        let encoded_templates = self.state.read().await.encode_templates();
+        let _ = self.sender_clients.send(encoded_templates);
+    }
+
+    async fn broadcast_schedule(&self) {
+        let encoded_schedule = self.state.read().await.encode_schedule(unix_now());
+        let _ = self.sender_clients.send(encoded_schedule);
+    }
+
+    /// Sends the layers followed by what's painted on those over the grid.
+    async fn broadcast_layers(&self) {
+        let state_guard = self.state.read().await;
+        let encoded_layers = state_guard.encode_layers();
+        let encoded_overlays = state_guard.encode_overlays().await;
+        drop(state_guard);
+        let _ = self.sender_clients.send(encoded_layers);
+        for encoded_overlay in encoded_overlays {
+            let _ = self.sender_clients.send(encoded_overlay);
+        }
+    }
+
+    /// Applies the pixels and sends those inside the grid to the clients, as one `DeltaGrid`
+    /// for the grid and one `LayerDelta` for each layer over it, returning how many were
+    /// applied. Pixels from a placer are recorded in the history, the others aren't.
+    async fn paint(
+        &mut self,
+        pixels: Vec<LayerPixel>,
+        placer: Option<(SessionId, IpAddr)>,
+    ) -> usize {
+        let state_guard = self.state.read().await;
+        let mut grid_delta = Vec::new();
+        let mut layer_deltas: BTreeMap<u32, (Vec<Pixel>, Vec<Position>)> = BTreeMap::new();
+        for pixel in pixels {
+            // Pixels cut off by a resize are gone, nothing to paint there.
+            let previous = match state_guard.set_layer_pixel(pixel).await {
+                Some(previous) => previous,
+                None => continue,
+            };
+            let LayerPixel { layer, x, y, color } = pixel;
+            if let Some((session, ip)) = placer {
+                self.history.record(PlacementRecord {
+                    layer,
+                    x,
+                    y,
+                    previous: previous.color,
+                    color,
+                    session,
+                    ip,
+                    placed_at: unix_now_millis(),
+                });
+                self.metrics.placements.inc();
+            }
+            if layer == 0 {
+                let pixel = Pixel {
+                    x,
+                    y,
+                    color: color.unwrap_or_default(),
+                };
+                state_guard.add_pixel_to_delta(pixel).await;
+                grid_delta.push(pixel);
+            } else {
+                let (painted, erased) = layer_deltas.entry(layer).or_default();
+                match color {
+                    Some(color) => painted.push(Pixel { x, y, color }),
+                    None => erased.push(Position { x, y }),
+                }
+            }
+        }
+        if !grid_delta.is_empty() {
+            state_guard.set_new_encoded_delta_data().await;
+        }
+        drop(state_guard);
+
+        let mut applied = grid_delta.len();
+        if !grid_delta.is_empty() {
+            let delta = DeltaGrid {
+                delta: SliceWrapper::Cooked(&grid_delta),
+            };
+            let _ = self
+                .sender_clients
+                .send(protocol::encode(DELTA_GRID_OPCODE, &delta));
+        }
+        for (layer, (painted, erased)) in layer_deltas {
+            applied += painted.len() + erased.len();
+            let delta = LayerDelta {
+                layer,
+                painted: SliceWrapper::Cooked(&painted),
+                erased: SliceWrapper::Cooked(&erased),
+            };
+            let _ = self
+                .sender_clients
+                .send(protocol::encode(LAYER_DELTA_OPCODE, &delta));
+        }
+        applied
+    }
+}
+
+/// Index of the named layer, the grid itself for `None`.
+fn find_layer(state: &State, name: Option<&str>) -> Result<u32, String> {
+    match name {
+        Some(name) => state
+            .layer_index(name)
+            .ok_or_else(|| format!("no layer is named {}", name)),
+        None => Ok(0),
+    }
+}
+
+/// Waits until the Unix time in seconds, forever for `None`.
+async fn sleep_until(time: Option<u64>) {
+    match time {
+        Some(time) => {
+            let at = UNIX_EPOCH + Duration::from_secs(time);
+            let remaining = at.duration_since(SystemTime::now()).unwrap_or_default();
+            tokio::time::sleep(remaining).await
+        }
+        None => future::pending().await,
+    }
+}
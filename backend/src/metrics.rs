@@ -0,0 +1,227 @@
+use std::{sync::Arc, time::Duration};
+
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    time,
+};
+use tracing::{debug, info, warn};
+
+/// How long a scraper has to send its request and take the response before the socket is
+/// dropped, so idle connections don't pile up.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Prometheus metrics describing the server, exposed over HTTP by [`serve`].
+pub struct Metrics {
+    registry: Registry,
+    pub connected_clients: IntGauge,
+    pub spectators: IntGauge,
+    pub connection_errors: IntCounterVec,
+    pub refused_connections: IntCounterVec,
+    pub accept_errors: IntCounter,
+    pub idle_timeouts: IntCounter,
+    pub placements: IntCounter,
+    pub undone_placements: IntCounter,
+    pub rejected_placements: IntCounterVec,
+    pub manipulator_queue_depth: IntGaugeVec,
+    pub broadcast_lag_events: IntCounter,
+    pub snapshot_serialization_seconds: Histogram,
+    pub bytes_sent: IntCounter,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some("place".to_string()), None).unwrap();
+        let metrics = Metrics {
+            connected_clients: IntGauge::new(
+                "connected_clients",
+                "Clients with an open WebSocket connection.",
+            )
+            .unwrap(),
+            spectators: IntGauge::new(
+                "spectators",
+                "Connected clients that can watch the grid but not place pixels.",
+            )
+            .unwrap(),
+            connection_errors: IntCounterVec::new(
+                Opts::new(
+                    "connection_errors_total",
+                    "Connections that ended with an error, by kind.",
+                ),
+                &["kind"],
+            )
+            .unwrap(),
+            refused_connections: IntCounterVec::new(
+                Opts::new(
+                    "refused_connections_total",
+                    "Connections refused, or closed right after the handshake, by reason.",
+                ),
+                &["reason"],
+            )
+            .unwrap(),
+            accept_errors: IntCounter::new(
+                "accept_errors_total",
+                "Times accepting a connection failed, e.g. for lack of file descriptors.",
+            )
+            .unwrap(),
+            idle_timeouts: IntCounter::new(
+                "idle_timeouts_total",
+                "Connections closed after the client stayed silent past the idle limit.",
+            )
+            .unwrap(),
+            placements: IntCounter::new("placements_total", "Pixels placed on the grid.").unwrap(),
+            undone_placements: IntCounter::new(
+                "undone_placements_total",
+                "Placements taken back by their placer.",
+            )
+            .unwrap(),
+            rejected_placements: IntCounterVec::new(
+                Opts::new(
+                    "rejected_placements_total",
+                    "Placements that were not applied, by reason.",
+                ),
+                &["reason"],
+            )
+            .unwrap(),
+            manipulator_queue_depth: IntGaugeVec::new(
+                Opts::new(
+                    "manipulator_queue_depth",
+                    "Messages waiting for the grid manipulator, by canvas.",
+                ),
+                &["canvas"],
+            )
+            .unwrap(),
+            broadcast_lag_events: IntCounter::new(
+                "broadcast_lag_events_total",
+                "Times a client fell behind the broadcast channel and was resynced.",
+            )
+            .unwrap(),
+            snapshot_serialization_seconds: Histogram::with_opts(HistogramOpts::new(
+                "snapshot_serialization_seconds",
+                "Time spent encoding a full grid snapshot.",
+            ))
+            .unwrap(),
+            bytes_sent: IntCounter::new("bytes_sent_total", "Payload bytes sent to clients.")
+                .unwrap(),
+            registry,
+        };
+
+        let registry = &metrics.registry;
+        registry
+            .register(Box::new(metrics.connected_clients.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.spectators.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.connection_errors.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.refused_connections.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.accept_errors.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.idle_timeouts.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.placements.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.undone_placements.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.rejected_placements.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.manipulator_queue_depth.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.broadcast_lag_events.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.snapshot_serialization_seconds.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(metrics.bytes_sent.clone()))
+            .unwrap();
+
+        metrics
+    }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .unwrap();
+        buf
+    }
+}
+
+/// Decrements the gauge when dropped, so it follows the lifetime of whatever holds it.
+pub struct GaugeGuard(IntGauge);
+
+impl GaugeGuard {
+    pub fn new(gauge: &IntGauge) -> Self {
+        gauge.inc();
+        GaugeGuard(gauge.clone())
+    }
+}
+
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+/// Answers `GET /metrics` with the current metrics, anything else gets a 404.
+pub async fn serve(listener: TcpListener, metrics: Arc<Metrics>) {
+    info!(addr = ?listener.local_addr().ok(), "serving metrics");
+    while let Ok((stream, addr)) = listener.accept().await {
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            match time::timeout(REQUEST_TIMEOUT, respond(stream, &metrics)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => debug!(%addr, error = %err, "metrics request failed"),
+                Err(_) => debug!(%addr, "metrics request timed out"),
+            }
+        });
+    }
+    warn!("metrics listener stopped");
+}
+
+async fn respond(mut stream: TcpStream, metrics: &Metrics) -> std::io::Result<()> {
+    // Scrapers send small requests, the request line is all we look at.
+    let mut buf = [0; 1024];
+    let read = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..read]);
+    let path = request.split_whitespace().nth(1);
+
+    let (status, content_type, body) = match path {
+        Some("/metrics") => (
+            "200 OK",
+            TextEncoder::new().format_type().to_string(),
+            metrics.encode(),
+        ),
+        _ => (
+            "404 Not Found",
+            "text/plain".to_string(),
+            b"not found\n".to_vec(),
+        ),
+    };
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await
+}
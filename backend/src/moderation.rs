@@ -0,0 +1,319 @@
+use std::{
+    fmt, fs, io,
+    net::IpAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+
+use crate::{session::SessionId, storage};
+
+/// Who a ban applies to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BanTarget {
+    Session(SessionId),
+    /// A CIDR range, a single address is stored as a `/32` or `/128`.
+    Network(IpNet),
+}
+
+impl BanTarget {
+    pub fn matches(&self, session: SessionId, ip: IpAddr) -> bool {
+        match self {
+            BanTarget::Session(banned) => session == *banned,
+            BanTarget::Network(network) => network.contains(&ip),
+        }
+    }
+}
+
+impl fmt::Display for BanTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BanTarget::Session(session) => write!(f, "session {}", session),
+            BanTarget::Network(network) => write!(f, "network {}", network),
+        }
+    }
+}
+
+impl FromStr for BanTarget {
+    type Err = String;
+
+    /// Accepts an IP address, a CIDR range or a session id.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(ip) = s.parse::<IpAddr>() {
+            return Ok(BanTarget::Network(ip.into()));
+        }
+        if let Ok(network) = s.parse::<IpNet>() {
+            return Ok(BanTarget::Network(network.trunc()));
+        }
+        s.parse()
+            .map(BanTarget::Session)
+            .map_err(|_| format!("{:?} is neither an IP, a CIDR range nor a session id", s))
+    }
+}
+
+/// How far a ban goes, a banned client can't even connect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sanction {
+    /// Can watch the canvas but not place pixels.
+    Mute,
+    Ban,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Ban {
+    pub target: BanTarget,
+    pub sanction: Sanction,
+    /// Unix time in seconds after which the ban is lifted, `None` for a permanent one.
+    pub expires_at: Option<u64>,
+    pub reason: Option<String>,
+}
+
+impl Ban {
+    fn is_active(&self, now: u64) -> bool {
+        self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+
+    /// Explanation shown to the banned client.
+    pub fn notice(&self) -> String {
+        let mut notice = match self.sanction {
+            Sanction::Mute => "You are muted".to_string(),
+            Sanction::Ban => "You are banned".to_string(),
+        };
+        if let Some(expires_at) = self.expires_at {
+            notice += &format!(" for {}", format_remaining(expires_at));
+        }
+        if let Some(reason) = &self.reason {
+            notice += &format!(": {}", reason);
+        }
+        notice
+    }
+}
+
+impl fmt::Display for Ban {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sanction = match self.sanction {
+            Sanction::Mute => "muted",
+            Sanction::Ban => "banned",
+        };
+        write!(f, "{} {}", self.target, sanction)?;
+        match self.expires_at {
+            Some(expires_at) => write!(f, " for {}", format_remaining(expires_at))?,
+            None => write!(f, " permanently")?,
+        }
+        if let Some(reason) = &self.reason {
+            write!(f, ": {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bans and mutes, saved to a JSON file after every change so they survive restarts.
+pub struct BanList {
+    path: PathBuf,
+    bans: Vec<Ban>,
+}
+
+impl BanList {
+    /// Reads the list saved at `path`, starting empty when there is none yet.
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let bans = match fs::read(&path) {
+            Ok(contents) => serde_json::from_slice(&contents)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(BanList { path, bans })
+    }
+
+    /// Writes the active bans, forgetting the expired ones.
+    pub fn save(&mut self) -> io::Result<()> {
+        let now = unix_now();
+        self.bans.retain(|ban| ban.is_active(now));
+        storage::save_json(&self.path, &self.bans)
+    }
+
+    /// Adds the ban, replacing any previous one on the same target.
+    pub fn add(&mut self, ban: Ban) -> io::Result<()> {
+        self.bans.retain(|existing| existing.target != ban.target);
+        self.bans.push(ban);
+        self.save()
+    }
+
+    /// Lifts the ban on the target, returns `false` if there was none.
+    pub fn remove(&mut self, target: BanTarget) -> io::Result<bool> {
+        let len = self.bans.len();
+        self.bans.retain(|ban| ban.target != target);
+        if self.bans.len() == len {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// The harshest active ban matching the client, if any.
+    pub fn lookup(&self, session: SessionId, ip: IpAddr) -> Option<&Ban> {
+        let now = unix_now();
+        self.bans
+            .iter()
+            .filter(|ban| ban.is_active(now) && ban.target.matches(session, ip))
+            .max_by_key(|ban| ban.sanction)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Ban> {
+        let now = unix_now();
+        self.bans.iter().filter(move |ban| ban.is_active(now))
+    }
+}
+
+/// Time left until `expires_at`, in its largest whole unit, e.g. `3 more hours`.
+fn format_remaining(expires_at: u64) -> String {
+    let remaining = expires_at.saturating_sub(unix_now());
+    let (amount, unit) = match remaining {
+        0..=119 => (remaining, "seconds"),
+        120..=7199 => (remaining.div_ceil(60), "minutes"),
+        7200..=172_799 => (remaining.div_ceil(3600), "hours"),
+        _ => (remaining.div_ceil(86400), "days"),
+    };
+    format!("{} more {}", amount, unit)
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+pub fn unix_now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A list saved to a file of its own in the temporary directory.
+    fn ban_list() -> BanList {
+        let path = std::env::temp_dir().join(format!("bans-{}.json", SessionId::random()));
+        BanList::load(path).unwrap()
+    }
+
+    fn ban(target: &str, sanction: Sanction, expires_at: Option<u64>) -> Ban {
+        Ban {
+            target: target.parse().unwrap(),
+            sanction,
+            expires_at,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn matches_addresses_in_a_range() {
+        let mut bans = ban_list();
+        bans.add(ban("10.0.0.0/8", Sanction::Ban, None)).unwrap();
+        let session = SessionId::random();
+        assert!(bans.lookup(session, "10.1.2.3".parse().unwrap()).is_some());
+        assert!(bans.lookup(session, "11.0.0.1".parse().unwrap()).is_none());
+        fs::remove_file(&bans.path).unwrap();
+    }
+
+    #[test]
+    fn stores_ranges_by_their_network() {
+        assert_eq!(
+            "10.1.2.3/8".parse::<BanTarget>(),
+            "10.0.0.0/8".parse::<BanTarget>()
+        );
+        assert_eq!(
+            "::1".parse::<BanTarget>(),
+            Ok(BanTarget::Network("::1/128".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn matches_banned_sessions_from_any_address() {
+        let mut bans = ban_list();
+        let session = SessionId::random();
+        bans.add(ban(&session.to_string(), Sanction::Ban, None))
+            .unwrap();
+        assert!(bans.lookup(session, "10.1.2.3".parse().unwrap()).is_some());
+        assert!(bans
+            .lookup(SessionId::random(), "10.1.2.3".parse().unwrap())
+            .is_none());
+        fs::remove_file(&bans.path).unwrap();
+    }
+
+    #[test]
+    fn ignores_expired_bans() {
+        let mut bans = ban_list();
+        bans.add(ban("10.0.0.1", Sanction::Ban, Some(unix_now() - 1)))
+            .unwrap();
+        bans.add(ban("10.0.0.2", Sanction::Ban, Some(unix_now() + 60)))
+            .unwrap();
+        let session = SessionId::random();
+        assert!(bans.lookup(session, "10.0.0.1".parse().unwrap()).is_none());
+        assert!(bans.lookup(session, "10.0.0.2".parse().unwrap()).is_some());
+        assert_eq!(bans.iter().count(), 1);
+        fs::remove_file(&bans.path).unwrap();
+    }
+
+    #[test]
+    fn the_harshest_ban_wins() {
+        let mut bans = ban_list();
+        let session = SessionId::random();
+        bans.add(ban("10.0.0.0/8", Sanction::Mute, None)).unwrap();
+        bans.add(ban(&session.to_string(), Sanction::Ban, None))
+            .unwrap();
+        let ip = "10.0.0.1".parse().unwrap();
+        assert_eq!(
+            bans.lookup(session, ip).map(|ban| ban.sanction),
+            Some(Sanction::Ban)
+        );
+        assert_eq!(
+            bans.lookup(SessionId::random(), ip).map(|ban| ban.sanction),
+            Some(Sanction::Mute)
+        );
+        fs::remove_file(&bans.path).unwrap();
+    }
+
+    #[test]
+    fn a_new_ban_replaces_the_one_on_the_same_target() {
+        let mut bans = ban_list();
+        bans.add(ban("10.0.0.1", Sanction::Ban, None)).unwrap();
+        bans.add(ban("10.0.0.1", Sanction::Mute, None)).unwrap();
+        assert_eq!(bans.iter().count(), 1);
+        assert!(bans.remove("10.0.0.1".parse().unwrap()).unwrap());
+        assert!(!bans.remove("10.0.0.1".parse().unwrap()).unwrap());
+        fs::remove_file(&bans.path).unwrap();
+    }
+
+    #[test]
+    fn survives_a_reload() {
+        let mut bans = ban_list();
+        let session = SessionId::random();
+        bans.add(ban(&session.to_string(), Sanction::Mute, None))
+            .unwrap();
+        bans.add(ban("10.0.0.0/8", Sanction::Ban, Some(unix_now() + 60)))
+            .unwrap();
+        bans.add(ban("10.0.0.1", Sanction::Ban, Some(unix_now() - 1)))
+            .unwrap();
+
+        let reloaded = BanList::load(&bans.path).unwrap();
+        fs::remove_file(&bans.path).unwrap();
+        let targets: Vec<BanTarget> = reloaded.bans.iter().map(|ban| ban.target).collect();
+        assert_eq!(
+            targets,
+            vec![BanTarget::Session(session), "10.0.0.0/8".parse().unwrap()]
+        );
+        let ip = "10.0.0.1".parse().unwrap();
+        assert_eq!(
+            reloaded.lookup(session, ip).map(|ban| ban.sanction),
+            Some(Sanction::Ban)
+        );
+    }
+}
@@ -1,11 +1,26 @@
-use std::{iter, ops::DerefMut};
+use std::{
+    collections::HashMap,
+    fmt, iter, mem,
+    net::IpAddr,
+    ops::DerefMut,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use bebop::{Record, SliceWrapper, SubRecord};
-use rand::Rng;
-use tokio::sync::RwLock;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use tokio::sync::{oneshot, RwLock};
 
 // use crate::generated::grid::owned::*;
-use crate::generated::{self, grid::*};
+use crate::{
+    admin::CanvasCommand,
+    auth::Identity,
+    generated::{self, grid::*},
+    protocol,
+    session::SessionId,
+    templates::Template,
+};
 
 impl Color {
     pub fn new(red: u8, green: u8, blue: u8) -> Self {
@@ -19,6 +34,366 @@ impl Default for Color {
     }
 }
 
+/// Written like `ff4500`.
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode([self.red, self.green, self.blue]))
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Accepts colors like `ff4500`, with or without a leading `#`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match hex::decode(s.trim_start_matches('#')).as_deref() {
+            Ok(&[red, green, blue]) => Ok(Color::new(red, green, blue)),
+            _ => Err(format!("{:?} is not a color like ff4500", s)),
+        }
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// A rectangle of the grid, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Region {
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && x - self.x < self.width && y >= self.y && y - self.y < self.height
+    }
+}
+
+/// A layer of the canvas, the first one being the grid itself.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LayerSettings {
+    pub name: String,
+    /// Only moderators can paint the layer, through the admin commands.
+    #[serde(default)]
+    pub restricted: bool,
+}
+
+impl LayerSettings {
+    /// The grid, open to everyone, for canvases without layers.
+    pub fn base() -> Self {
+        LayerSettings {
+            name: "base".to_string(),
+            restricted: false,
+        }
+    }
+}
+
+impl fmt::Display for LayerSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.restricted {
+            write!(f, " (moderators only)")?;
+        }
+        Ok(())
+    }
+}
+
+/// A pixel of any layer. `None` makes a pixel of a layer over the grid transparent again,
+/// and paints the grid itself white.
+#[derive(Clone, Copy, Debug)]
+pub struct LayerPixel {
+    pub layer: u32,
+    pub x: u32,
+    pub y: u32,
+    pub color: Option<Color>,
+}
+
+/// Pixels a privileged client paints on a layer at once, every one of them or none.
+#[derive(Clone, Debug)]
+pub enum Batch {
+    Rect {
+        region: Region,
+        color: Color,
+    },
+    Pixels(Vec<Pixel>),
+    /// An image `width` pixels wide with its top left corner at `x`, `y`, as runs of one
+    /// color read left to right and top to bottom.
+    Bitmap {
+        x: u32,
+        y: u32,
+        width: u32,
+        runs: Vec<ColorRun>,
+    },
+}
+
+impl Batch {
+    /// Whether every pixel is inside a grid of this size.
+    pub fn fits(&self, grid_width: u32, grid_height: u32) -> bool {
+        let fits = |x: u32, y: u32, width: u32, height: u32| {
+            u64::from(x) + u64::from(width) <= u64::from(grid_width)
+                && u64::from(y) + u64::from(height) <= u64::from(grid_height)
+        };
+        match self {
+            Batch::Rect { region, .. } => fits(region.x, region.y, region.width, region.height),
+            Batch::Pixels(pixels) => pixels
+                .iter()
+                .all(|pixel| pixel.x < grid_width && pixel.y < grid_height),
+            Batch::Bitmap { x, y, width, runs } => {
+                let length: u64 = runs.iter().map(|run| u64::from(run.length)).sum();
+                let height = match *width {
+                    0 => return length == 0,
+                    width => length.div_ceil(u64::from(width)),
+                };
+                match u32::try_from(height) {
+                    Ok(height) => fits(*x, *y, *width, height),
+                    Err(_) => false,
+                }
+            }
+        }
+    }
+
+    /// The pixels to paint, only meant for batches that fit in the grid.
+    pub fn into_pixels(self) -> Vec<Pixel> {
+        match self {
+            Batch::Rect { region, color } => (region.y..region.y + region.height)
+                .flat_map(|y| {
+                    (region.x..region.x + region.width).map(move |x| Pixel { x, y, color })
+                })
+                .collect(),
+            Batch::Pixels(pixels) => pixels,
+            Batch::Bitmap { x, y, width, runs } => runs
+                .into_iter()
+                .flat_map(|run| iter::repeat_n(run.color, run.length as usize))
+                .enumerate()
+                .map(|(index, color)| Pixel {
+                    x: x + (index as u32 % width),
+                    y: y + (index as u32 / width),
+                    color,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Work for the grid manipulator, which applies every change to the grid one at a time.
+pub enum GridCommand {
+    Place(Placement),
+    /// Pixels from a privileged client, replies like a placement.
+    PaintBatch(BatchPlacement),
+    /// A client taking back its last placement, replies like a placement.
+    Undo(Undo),
+    /// A moderator's command, replies with what happened or why it failed.
+    Admin {
+        command: CanvasCommand,
+        reply: oneshot::Sender<Result<String, String>>,
+    },
+    /// The canvas was deleted, the manipulator drops what's left in its queue.
+    Stop,
+}
+
+/// A pixel sent by a client, waiting for the grid manipulator to apply it.
+pub struct Placement {
+    pub layer: u32,
+    pub pixel: Pixel,
+    pub session: SessionId,
+    /// Who placed it, for authenticated clients, checked against protected regions.
+    pub identity: Option<Arc<Identity>>,
+    /// Address of the client, checked against IP bans.
+    pub ip: IpAddr,
+    /// Public id of the placer broadcast with the pixel, `None` to keep it anonymous.
+    pub placer: Option<Arc<str>>,
+    pub reply: oneshot::Sender<PlacementStatus>,
+}
+
+/// A [`Batch`] sent by a privileged client, which skips the cooldown but is refused as a
+/// whole when any of its pixels would be refused as a placement.
+pub struct BatchPlacement {
+    pub layer: u32,
+    pub batch: Batch,
+    pub session: SessionId,
+    /// Who sent it, checked against protected regions.
+    pub identity: Option<Arc<Identity>>,
+    pub ip: IpAddr,
+    pub reply: oneshot::Sender<PlacementStatus>,
+}
+
+/// A client asking to restore what its most recent placement painted over.
+pub struct Undo {
+    pub session: SessionId,
+    pub ip: IpAddr,
+    pub reply: oneshot::Sender<PlacementStatus>,
+}
+
+/// What happened to a [`Placement`], reported back to the client that sent it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlacementStatus {
+    Accepted,
+    /// The grid manipulator queue stayed full, the client should retry later.
+    ServerBusy,
+    OutOfBounds,
+    /// The session placed a pixel too recently, it can place again once `remaining` passed.
+    RateLimited {
+        remaining: Duration,
+    },
+    /// The session or its address is banned or muted.
+    Banned,
+    /// The color isn't part of the palette.
+    InvalidColor,
+    /// The canvas is locked or the event hasn't started yet.
+    CanvasLocked,
+    /// The event is over.
+    CanvasEnded,
+    /// The pixel is in a protected region the placer wasn't granted.
+    Protected,
+    /// Only moderators can paint the layer.
+    LayerRestricted,
+    /// The connection is read-only, authentication is required to place pixels.
+    Unauthorized,
+    /// The client connected as a spectator, which never places pixels.
+    Spectating,
+    /// The client has no placement left that it can still undo.
+    NothingToUndo,
+}
+
+impl PlacementStatus {
+    pub fn code(self) -> u32 {
+        match self {
+            PlacementStatus::Accepted => PLACEMENT_ACCEPTED,
+            PlacementStatus::ServerBusy => PLACEMENT_SERVER_BUSY,
+            PlacementStatus::OutOfBounds => PLACEMENT_OUT_OF_BOUNDS,
+            PlacementStatus::RateLimited { .. } => PLACEMENT_RATE_LIMITED,
+            PlacementStatus::Banned => PLACEMENT_BANNED,
+            PlacementStatus::InvalidColor => PLACEMENT_INVALID_COLOR,
+            PlacementStatus::CanvasLocked => PLACEMENT_CANVAS_LOCKED,
+            PlacementStatus::CanvasEnded => PLACEMENT_CANVAS_ENDED,
+            PlacementStatus::Protected => PLACEMENT_PROTECTED,
+            PlacementStatus::LayerRestricted => PLACEMENT_LAYER_RESTRICTED,
+            PlacementStatus::Unauthorized => PLACEMENT_UNAUTHORIZED,
+            PlacementStatus::Spectating => PLACEMENT_SPECTATING,
+            PlacementStatus::NothingToUndo => PLACEMENT_NOTHING_TO_UNDO,
+        }
+    }
+}
+
+/// Whether the canvas accepts placements right now.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CanvasStatus {
+    Open,
+    Locked,
+    Ended,
+}
+
+impl CanvasStatus {
+    pub fn code(self) -> u32 {
+        match self {
+            CanvasStatus::Open => CANVAS_OPEN,
+            CanvasStatus::Locked => CANVAS_LOCKED,
+            CanvasStatus::Ended => CANVAS_ENDED,
+        }
+    }
+}
+
+impl fmt::Display for CanvasStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CanvasStatus::Open => "open",
+            CanvasStatus::Locked => "locked",
+            CanvasStatus::Ended => "ended",
+        })
+    }
+}
+
+/// When the canvas accepts placements, for events running over a fixed window.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    /// Set by a moderator, placements are refused until it's lifted whatever the times.
+    pub locked: bool,
+    /// Unix time in seconds before which the canvas stays locked.
+    pub starts_at: Option<u64>,
+    /// Unix time in seconds from which the canvas is ended.
+    pub ends_at: Option<u64>,
+}
+
+impl Schedule {
+    pub fn status(&self, now: u64) -> CanvasStatus {
+        if self.ends_at.is_some_and(|ends_at| now >= ends_at) {
+            CanvasStatus::Ended
+        } else if self.locked || self.starts_at.is_some_and(|starts_at| now < starts_at) {
+            CanvasStatus::Locked
+        } else {
+            CanvasStatus::Open
+        }
+    }
+
+    /// The next time the status changes on its own, if it ever does.
+    pub fn next_change(&self, now: u64) -> Option<u64> {
+        [self.starts_at, self.ends_at]
+            .into_iter()
+            .flatten()
+            .filter(|&time| time > now)
+            .min()
+    }
+}
+
+/// When each session last placed a pixel, to make it wait between placements.
+pub struct Cooldowns {
+    cooldown: Duration,
+    last_placements: HashMap<SessionId, Instant>,
+    prune_at: usize,
+}
+
+impl Cooldowns {
+    pub fn new(cooldown: Duration) -> Self {
+        Cooldowns {
+            cooldown,
+            last_placements: HashMap::new(),
+            prune_at: 1024,
+        }
+    }
+
+    /// Changes the time between placements, still counted from each session's last one.
+    pub fn set_cooldown(&mut self, cooldown: Duration) {
+        self.cooldown = cooldown;
+    }
+
+    /// How long the session still has to wait before placing a pixel, if at all.
+    pub fn remaining(&self, session: SessionId, now: Instant) -> Option<Duration> {
+        let last_placement = self.last_placements.get(&session)?;
+        self.cooldown
+            .checked_sub(now.saturating_duration_since(*last_placement))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    pub fn record(&mut self, session: SessionId, now: Instant) {
+        if self.cooldown.is_zero() {
+            return;
+        }
+        // Sessions that waited out their cooldown don't need to be remembered anymore.
+        if self.last_placements.len() >= self.prune_at {
+            let cooldown = self.cooldown;
+            self.last_placements.retain(|_, last_placement| {
+                now.saturating_duration_since(*last_placement) < cooldown
+            });
+            self.prune_at = (self.last_placements.len() * 2).max(1024);
+        }
+        self.last_placements.insert(session, now);
+    }
+}
+
 pub struct State {
     pub grid: RwLock<Vec<Vec<Color>>>,
     pub delta: RwLock<Vec<Pixel>>,
@@ -27,6 +402,15 @@ pub struct State {
     encoded_delta_data: RwLock<Vec<u8>>,
     encoded_delta: RwLock<Vec<u8>>,
     current_grid_size: (usize, usize),
+    schedule: Schedule,
+    /// Colors clients may place, any color when empty.
+    palette: Vec<Color>,
+    /// Bottom first, the first one describing the grid.
+    layers: Vec<LayerSettings>,
+    /// Painted pixels of the layers over the grid, by position.
+    overlays: Vec<RwLock<HashMap<(u32, u32), Color>>>,
+    /// Counting the pixels left to paint as the layers change.
+    templates: Vec<Template>,
 }
 
 impl State {
@@ -39,30 +423,220 @@ impl State {
             encoded_delta_data: RwLock::new(Vec::new()),
             encoded_delta: RwLock::new(Vec::new()),
             current_grid_size: (0, 0), // (width, height)
+            schedule: Schedule::default(),
+            palette: Vec::new(),
+            layers: vec![LayerSettings::base()],
+            overlays: Vec::new(),
+            templates: Vec::new(),
         }
     }
 
+    pub fn get_schedule(&self) -> Schedule {
+        self.schedule
+    }
+
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        self.schedule = schedule;
+    }
+
+    /// The schedule and the status it gives at `now` as a message for the clients.
+    pub fn encode_schedule(&self, now: u64) -> Vec<u8> {
+        let schedule = CanvasSchedule {
+            status: self.schedule.status(now).code(),
+            starts_at: self.schedule.starts_at.unwrap_or(0),
+            ends_at: self.schedule.ends_at.unwrap_or(0),
+        };
+        protocol::encode(CANVAS_SCHEDULE_OPCODE, &schedule)
+    }
+
+    pub fn get_palette(&self) -> &[Color] {
+        &self.palette
+    }
+
+    pub fn set_palette(&mut self, palette: Vec<Color>) {
+        self.palette = palette;
+    }
+
+    pub fn allows_color(&self, color: Color) -> bool {
+        self.palette.is_empty() || self.palette.contains(&color)
+    }
+
+    /// The palette as a message for the clients.
+    pub fn encode_palette(&self) -> Vec<u8> {
+        let palette = Palette {
+            colors: SliceWrapper::Cooked(&self.palette),
+        };
+        protocol::encode(PALETTE_OPCODE, &palette)
+    }
+
+    pub fn get_layers(&self) -> &[LayerSettings] {
+        &self.layers
+    }
+
+    /// Replaces the layers with blank ones, keeping the grid. A base layer is added when
+    /// there are none.
+    pub fn set_layers(&mut self, mut layers: Vec<LayerSettings>) {
+        if layers.is_empty() {
+            layers.push(LayerSettings::base());
+        }
+        self.overlays = iter::repeat_with(Default::default)
+            .take(layers.len() - 1)
+            .collect();
+        self.layers = layers;
+    }
+
+    pub fn layer_index(&self, name: &str) -> Option<u32> {
+        self.layers
+            .iter()
+            .position(|layer| layer.name == name)
+            .map(|index| index as u32)
+    }
+
+    /// Stacks a blank layer on top of the others.
+    pub fn add_layer(&mut self, layer: LayerSettings) {
+        self.layers.push(layer);
+        self.overlays.push(Default::default());
+    }
+
+    /// Removes a layer over the grid with its templates, the ones above it move down.
+    pub fn remove_layer(&mut self, index: u32) {
+        if index > 0 && (index as usize) < self.layers.len() {
+            self.layers.remove(index as usize);
+            self.overlays.remove(index as usize - 1);
+            self.templates.retain(|template| template.layer != index);
+            for template in &mut self.templates {
+                if template.layer > index {
+                    template.layer -= 1;
+                }
+            }
+        }
+    }
+
+    pub fn set_layer_restricted(&mut self, index: u32, restricted: bool) {
+        if let Some(layer) = self.layers.get_mut(index as usize) {
+            layer.restricted = restricted;
+        }
+    }
+
+    /// The layers as a message for the clients.
+    pub fn encode_layers(&self) -> Vec<u8> {
+        let layers = Layers {
+            layers: self
+                .layers
+                .iter()
+                .map(|layer| Layer {
+                    name: &layer.name,
+                    restricted: layer.restricted,
+                })
+                .collect(),
+        };
+        protocol::encode(LAYERS_OPCODE, &layers)
+    }
+
+    /// The painted pixels of every layer over the grid, one message per layer.
+    pub async fn encode_overlays(&self) -> Vec<Vec<u8>> {
+        let mut encoded_overlays = Vec::with_capacity(self.overlays.len());
+        for (index, overlay) in self.overlays.iter().enumerate() {
+            let overlay_guard = overlay.read().await;
+            let pixels: Vec<Pixel> = overlay_guard
+                .iter()
+                .map(|(&(x, y), &color)| Pixel { x, y, color })
+                .collect();
+            let layer_grid = LayerGrid {
+                layer: index as u32 + 1,
+                pixels: SliceWrapper::Cooked(&pixels),
+            };
+            encoded_overlays.push(protocol::encode(LAYER_GRID_OPCODE, &layer_grid));
+        }
+        encoded_overlays
+    }
+
+    pub fn get_templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    pub fn find_template(&self, name: &str) -> Option<&Template> {
+        self.templates.iter().find(|template| template.name == name)
+    }
+
+    /// Replaces the templates, counting what's left to paint of each.
+    pub async fn set_templates(&mut self, templates: Vec<Template>) {
+        for template in &templates {
+            self.count_differing(template).await;
+        }
+        self.templates = templates;
+    }
+
+    /// Adds the template, replacing any previous one with the same name.
+    pub async fn add_template(&mut self, template: Template) {
+        self.count_differing(&template).await;
+        self.templates
+            .retain(|existing| existing.name != template.name);
+        self.templates.push(template);
+    }
+
+    /// Returns `false` if there was no template with this name.
+    pub fn remove_template(&mut self, name: &str) -> bool {
+        let len = self.templates.len();
+        self.templates.retain(|template| template.name != name);
+        self.templates.len() != len
+    }
+
+    /// The templates without their images, with what's left to paint of each, as a message
+    /// for the clients.
+    pub fn encode_templates(&self) -> Vec<u8> {
+        let templates = Templates {
+            templates: self.templates.iter().map(Template::info).collect(),
+        };
+        protocol::encode(TEMPLATES_OPCODE, &templates)
+    }
+
+    /// Counts the opaque pixels of the template inside the grid its layer doesn't match.
+    async fn count_differing(&self, template: &Template) {
+        let (width, height) = (self.get_grid_width(), self.get_grid_height());
+        let inside = |&(x, y, _): &(u32, u32, Color)| (x as usize) < width && (y as usize) < height;
+        let differing = if template.layer == 0 {
+            let grid_guard = self.grid.read().await;
+            template
+                .opaque_pixels()
+                .filter(inside)
+                .filter(|&(x, y, color)| grid_guard[y as usize][x as usize] != color)
+                .count()
+        } else {
+            let overlay_guard = match self.overlays.get(template.layer as usize - 1) {
+                Some(overlay) => overlay.read().await,
+                None => return template.set_differing(0),
+            };
+            template
+                .opaque_pixels()
+                .filter(inside)
+                .filter(|&(x, y, color)| overlay_guard.get(&(x, y)) != Some(&color))
+                .count()
+        };
+        template.set_differing(differing as u32);
+    }
+
     pub fn get_grid_width(&self) -> usize {
-        return self.current_grid_size.0;
+        self.current_grid_size.0
     }
 
     pub fn get_grid_height(&self) -> usize {
-        return self.current_grid_size.0;
+        self.current_grid_size.1
     }
 
     pub async fn set_grid_size(&mut self, new_width: usize, new_height: usize) {
         self.set_grid_height(new_height).await;
         self.set_grid_width(new_width).await;
-        let mut grid_guard = self.grid.write().await;
-        let mut rng = rand::thread_rng();
-        // Test to place random colors all over the grid
-        // for row_guard in grid_guard.iter_mut() {
-        //     for color in row_guard {
-        //         color.red = rng.gen_range(0..=255);
-        //         color.green = rng.gen_range(0..=255);
-        //         color.blue = rng.gen_range(0..=255);
-        //     }
-        // }
+        // Layers over the grid are cut to its size too.
+        for overlay in &self.overlays {
+            overlay
+                .write()
+                .await
+                .retain(|&(x, y), _| (x as usize) < new_width && (y as usize) < new_height);
+        }
+        for template in &self.templates {
+            self.count_differing(template).await;
+        }
     }
 
     pub async fn set_grid_width(&mut self, new_width: usize) {
@@ -104,7 +678,7 @@ impl State {
         let mut buf: Vec<u8> = Vec::with_capacity(grid.serialized_size());
         grid.serialize(&mut buf).unwrap();
 
-        return buf;
+        buf
     }
 
     async fn serialize_delta(&self) -> Vec<u8> {
@@ -117,7 +691,7 @@ impl State {
         let mut buf: Vec<u8> = Vec::with_capacity(delta.serialized_size());
         delta.serialize(&mut buf).unwrap();
 
-        return buf;
+        buf
     }
 
     pub async fn set_new_encoded_grid_data(&self) {
@@ -130,7 +704,9 @@ impl State {
         };
         let mut encoded_grid_data_guard = self.encoded_grid_data.write().await;
         *encoded_grid_data_guard = Vec::with_capacity(bebop_data.serialized_size());
-        bebop_data.serialize(encoded_grid_data_guard.deref_mut());
+        bebop_data
+            .serialize(encoded_grid_data_guard.deref_mut())
+            .unwrap();
         self.delta.write().await.clear();
         self.set_new_encoded_delta_data().await;
     }
@@ -147,35 +723,85 @@ impl State {
         let mut encoded_delta_data_guard = self.encoded_delta_data.write().await;
 
         *encoded_delta_data_guard = Vec::with_capacity(bebop_data.serialized_size());
-        bebop_data.serialize(encoded_delta_data_guard.deref_mut());
+        bebop_data
+            .serialize(encoded_delta_data_guard.deref_mut())
+            .unwrap();
     }
 
     pub async fn get_encoded_grid_data_cloned(&self) -> Vec<u8> {
-        return self.encoded_grid_data.read().await.clone();
+        self.encoded_grid_data.read().await.clone()
     }
 
     pub async fn get_encoded_delta_data_cloned(&self) -> Vec<u8> {
-        return self.encoded_delta_data.read().await.clone();
+        self.encoded_delta_data.read().await.clone()
     }
 
-    pub async fn set_pixel(&self, pixel: Pixel) {
+    /// Colors the pixel and returns the color it had, `None` without changing anything when
+    /// it's outside the grid.
+    pub async fn set_pixel(&self, pixel: Pixel) -> Option<Color> {
         let mut grid_guard = self.grid.write().await;
 
-        match grid_guard.get_mut(pixel.y as usize) {
-            Some(row) => match row.get_mut(pixel.x as usize) {
-                Some(color) => *color = pixel.color,
-                None => return,
-            },
-            None => return,
+        grid_guard
+            .get_mut(pixel.y as usize)
+            .and_then(|row| row.get_mut(pixel.x as usize))
+            .map(|color| mem::replace(color, pixel.color))
+    }
+
+    /// The color of a pixel of any layer, `None` when it's transparent or outside the grid.
+    pub async fn get_layer_color(&self, layer: u32, x: u32, y: u32) -> Option<Color> {
+        if layer == 0 {
+            let grid_guard = self.grid.read().await;
+            return grid_guard.get(y as usize)?.get(x as usize).copied();
         }
+        let overlay_guard = self.overlays.get(layer as usize - 1)?.read().await;
+        overlay_guard.get(&(x, y)).copied()
     }
+
+    /// Colors the pixel of its layer and returns the pixel putting back what it covered,
+    /// `None` without changing anything when it's outside the grid or there's no such layer.
+    pub async fn set_layer_pixel(&self, pixel: LayerPixel) -> Option<LayerPixel> {
+        let LayerPixel { layer, x, y, color } = pixel;
+        let previous = if layer == 0 {
+            let pixel = Pixel {
+                x,
+                y,
+                color: color.unwrap_or_default(),
+            };
+            Some(self.set_pixel(pixel).await?)
+        } else {
+            if x as usize >= self.get_grid_width() || y as usize >= self.get_grid_height() {
+                return None;
+            }
+            let mut overlay_guard = self.overlays.get(layer as usize - 1)?.write().await;
+            match color {
+                Some(color) => overlay_guard.insert((x, y), color),
+                None => overlay_guard.remove(&(x, y)),
+            }
+        };
+        let after = match layer {
+            0 => Some(color.unwrap_or_default()),
+            _ => color,
+        };
+        for template in self
+            .templates
+            .iter()
+            .filter(|template| template.layer == layer)
+        {
+            template.record_change(x, y, previous, after);
+        }
+        Some(LayerPixel {
+            color: previous,
+            ..pixel
+        })
+    }
+
     async fn add_rows(&mut self, number_of_rows: usize) {
         let mut grid_guard = self.grid.write().await;
         grid_guard.append(
             // Repeat for each row
             &mut iter::repeat_with(|| {
                 // Repeat for each column
-                iter::repeat_with(|| Color::default())
+                iter::repeat_with(Color::default)
                     .take(self.current_grid_size.0)
                     .collect()
             })
@@ -190,7 +816,7 @@ impl State {
         let mut grid_guard = self.grid.write().await;
         for row in grid_guard.iter_mut() {
             row.append(
-                &mut iter::repeat_with(|| Color::default())
+                &mut iter::repeat_with(Color::default)
                     .take(number_of_columns)
                     .collect(),
             );
@@ -214,3 +840,85 @@ impl State {
         self.current_grid_size.0 = final_number_of_columns;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: u32, y: u32, width: u32, height: u32) -> Batch {
+        Batch::Rect {
+            region: Region {
+                x,
+                y,
+                width,
+                height,
+            },
+            color: Color::default(),
+        }
+    }
+
+    fn bitmap(x: u32, y: u32, width: u32, lengths: &[u32]) -> Batch {
+        let runs = lengths
+            .iter()
+            .map(|&length| ColorRun {
+                length,
+                color: Color::default(),
+            })
+            .collect();
+        Batch::Bitmap { x, y, width, runs }
+    }
+
+    #[test]
+    fn rectangles_fit_up_to_the_edges() {
+        assert!(rect(0, 0, 10, 10).fits(10, 10));
+        assert!(rect(9, 9, 1, 1).fits(10, 10));
+        assert!(!rect(9, 0, 2, 1).fits(10, 10));
+        assert!(!rect(0, 10, 1, 1).fits(10, 10));
+    }
+
+    #[test]
+    fn rectangles_past_u32_do_not_wrap_around() {
+        assert!(!rect(u32::MAX, 0, 2, 1).fits(10, 10));
+        assert!(!rect(0, u32::MAX, 1, u32::MAX).fits(10, 10));
+    }
+
+    #[test]
+    fn pixel_lists_fit_when_every_pixel_does() {
+        let pixel = |x, y| Pixel {
+            x,
+            y,
+            color: Color::default(),
+        };
+        assert!(Batch::Pixels(vec![pixel(0, 0), pixel(9, 9)]).fits(10, 10));
+        assert!(!Batch::Pixels(vec![pixel(0, 0), pixel(10, 0)]).fits(10, 10));
+        assert!(Batch::Pixels(Vec::new()).fits(10, 10));
+    }
+
+    #[test]
+    fn bitmaps_fit_by_their_rows() {
+        // 25 pixels 5 wide make 5 rows, the last one partly.
+        assert!(bitmap(5, 5, 5, &[20, 5]).fits(10, 10));
+        assert!(bitmap(5, 6, 5, &[20]).fits(10, 10));
+        assert!(!bitmap(5, 6, 5, &[20, 1]).fits(10, 10));
+        assert!(!bitmap(6, 0, 5, &[1]).fits(10, 10));
+    }
+
+    #[test]
+    fn bitmaps_without_width_only_fit_empty() {
+        assert!(bitmap(0, 0, 0, &[]).fits(10, 10));
+        assert!(!bitmap(0, 0, 0, &[1]).fits(10, 10));
+    }
+
+    #[test]
+    fn huge_bitmaps_do_not_overflow() {
+        assert!(!bitmap(0, 0, 1, &[u32::MAX, u32::MAX]).fits(10, 10));
+    }
+
+    #[test]
+    fn batches_that_fit_paint_inside_the_grid() {
+        let pixels = bitmap(8, 0, 2, &[3, 2]).into_pixels();
+        let positions: Vec<(u32, u32)> = pixels.iter().map(|pixel| (pixel.x, pixel.y)).collect();
+        assert_eq!(positions, vec![(8, 0), (9, 0), (8, 1), (9, 1), (8, 2)]);
+        assert_eq!(rect(1, 2, 3, 4).into_pixels().len(), 12);
+    }
+}
@@ -0,0 +1,238 @@
+use bebop::{DeResult, Record, SliceWrapper, SubRecord};
+
+use crate::generated::grid::*;
+
+/// Encodes the message and wraps it in `BebopData` so clients know how to decode it.
+pub fn encode<'raw, T: Record<'raw>>(opcode: u32, message: &T) -> Vec<u8> {
+    encode_for(PROTOCOL_VERSION, opcode, message)
+}
+
+/// Like [`encode`], for a client speaking an older version the message exists in.
+pub fn encode_for<'raw, T: Record<'raw>>(version: u32, opcode: u32, message: &T) -> Vec<u8> {
+    let mut encoded_message = Vec::with_capacity(message.serialized_size());
+    message.serialize(&mut encoded_message).unwrap();
+
+    let bebop_data = BebopData {
+        protocol_version: version,
+        opcode,
+        encoded_data: SliceWrapper::Raw(&encoded_message),
+    };
+    let mut buf = Vec::with_capacity(bebop_data.serialized_size());
+    bebop_data.serialize(&mut buf).unwrap();
+    buf
+}
+
+/// A `ServerNotice` for the client to show.
+pub fn encode_notice(code: u32, severity: u32, text: &str) -> Vec<u8> {
+    let notice = ServerNotice {
+        code,
+        severity,
+        text,
+    };
+    encode(SERVER_NOTICE_OPCODE, &notice)
+}
+
+/// Reads the `BebopData` envelope of a message sent by a client.
+pub fn decode(buf: &[u8]) -> DeResult<BebopData<'_>> {
+    BebopData::deserialize(buf)
+}
+
+/// The newest version both sides speak, `None` when the client only speaks versions the server
+/// doesn't.
+pub fn pick_version(client_versions: impl IntoIterator<Item = u32>) -> Option<u32> {
+    client_versions
+        .into_iter()
+        .filter(|version| (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(version))
+        .max()
+}
+
+/// The version that added the message, clients speaking an older one never receive it.
+///
+/// Version 1 is the schema as it was right before `PixelPlaced`: the messages up to
+/// `UndoPlacement` came while `ProtocolVersion` still said 1, so a client speaking it may
+/// only know the first grid, pixel and delta messages. Those clients skip opcodes they don't
+/// know, which is why the rest are still sent to them. Opcodes missing here are taken to be
+/// as new as the current version.
+fn introduced_in(opcode: u32) -> u32 {
+    match opcode {
+        GRID_OPCODE..=UNDO_PLACEMENT_OPCODE => 1,
+        PIXEL_PLACED_OPCODE => 2,
+        // Sent in whichever version the handshake settles on.
+        CLIENT_HELLO_OPCODE | SERVER_HELLO_OPCODE => MIN_PROTOCOL_VERSION,
+        SERVER_NOTICE_OPCODE => 3,
+        _ => PROTOCOL_VERSION,
+    }
+}
+
+/// Adapts a message encoded by [`encode`] to a client speaking `version`, `None` when the
+/// message doesn't exist in that version and has nothing to stand in for it.
+pub fn downgrade(mut buf: Vec<u8>, version: u32) -> Option<Vec<u8>> {
+    if version == PROTOCOL_VERSION {
+        return Some(buf);
+    }
+    let opcode = u32::from_le_bytes(buf.get(4..8)?.try_into().ok()?);
+    if introduced_in(opcode) > version {
+        return match opcode {
+            PIXEL_PLACED_OPCODE => legacy_placement(&buf, version),
+            _ => None,
+        };
+    }
+    // Besides the new messages, the versions are laid out the same.
+    buf[..4].copy_from_slice(&version.to_le_bytes());
+    Some(buf)
+}
+
+/// The `Pixel`, or `LayerDelta` on a layer over the grid, that announced a placement before
+/// `PixelPlaced` did.
+fn legacy_placement(buf: &[u8], version: u32) -> Option<Vec<u8>> {
+    let bebop_data = decode(buf).ok()?;
+    let placed = PixelPlaced::deserialize(&bebop_data.encoded_data).ok()?;
+    if placed.layer == 0 {
+        return Some(encode_for(version, PIXEL_OPCODE, &placed.pixel));
+    }
+    let pixels = [placed.pixel];
+    let delta = LayerDelta {
+        layer: placed.layer,
+        painted: SliceWrapper::Cooked(&pixels),
+        erased: SliceWrapper::Cooked(&[]),
+    };
+    Some(encode_for(version, LAYER_DELTA_OPCODE, &delta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version_and_opcode(buf: &[u8]) -> (u32, u32) {
+        let bebop_data = decode(buf).unwrap();
+        (bebop_data.protocol_version, bebop_data.opcode)
+    }
+
+    fn placed(layer: u32) -> Vec<u8> {
+        let placed = PixelPlaced {
+            layer,
+            pixel: Pixel {
+                x: 3,
+                y: 4,
+                color: Color::new(255, 69, 0),
+            },
+            placed_at: 1_700_000_000_000,
+            placer: "5f2a",
+        };
+        encode(PIXEL_PLACED_OPCODE, &placed)
+    }
+
+    #[test]
+    fn picks_the_newest_common_version() {
+        assert_eq!(pick_version([1, 2, 3]), Some(3));
+        assert_eq!(pick_version([2, 1]), Some(2));
+        assert_eq!(
+            pick_version([PROTOCOL_VERSION, PROTOCOL_VERSION + 1]),
+            Some(PROTOCOL_VERSION)
+        );
+    }
+
+    #[test]
+    fn finds_no_version_in_common() {
+        assert_eq!(pick_version([]), None);
+        assert_eq!(
+            pick_version([PROTOCOL_VERSION + 1, PROTOCOL_VERSION + 7]),
+            None
+        );
+        assert_eq!(pick_version([MIN_PROTOCOL_VERSION - 1]), None);
+    }
+
+    #[test]
+    fn leaves_messages_for_the_current_version_alone() {
+        let buf = encode_notice(NOTICE_ANNOUNCEMENT, NOTICE_INFO, "hello");
+        assert_eq!(downgrade(buf.clone(), PROTOCOL_VERSION), Some(buf));
+    }
+
+    #[test]
+    fn restamps_the_version_of_older_messages() {
+        let result = PlacementResult {
+            request_id: 7,
+            status: PLACEMENT_ACCEPTED,
+        };
+        let buf = encode(PLACEMENT_RESULT_OPCODE, &result);
+        for version in MIN_PROTOCOL_VERSION..PROTOCOL_VERSION {
+            let downgraded = downgrade(buf.clone(), version).unwrap();
+            assert_eq!(
+                version_and_opcode(&downgraded),
+                (version, PLACEMENT_RESULT_OPCODE)
+            );
+            // Only the version changed.
+            assert_eq!(downgraded[4..], buf[4..]);
+        }
+    }
+
+    #[test]
+    fn sends_messages_added_under_version_1_to_version_1_clients() {
+        let token = SessionToken { token: "abc.def" };
+        let buf = encode(SESSION_TOKEN_OPCODE, &token);
+        let downgraded = downgrade(buf, 1).unwrap();
+        assert_eq!(version_and_opcode(&downgraded), (1, SESSION_TOKEN_OPCODE));
+        let bebop_data = decode(&downgraded).unwrap();
+        let token = SessionToken::deserialize(&bebop_data.encoded_data).unwrap();
+        assert_eq!(token.token, "abc.def");
+    }
+
+    #[test]
+    fn drops_unknown_opcodes_for_older_versions() {
+        let result = PlacementResult {
+            request_id: 7,
+            status: PLACEMENT_ACCEPTED,
+        };
+        let buf = encode(SERVER_NOTICE_OPCODE + 1, &result);
+        assert_eq!(downgrade(buf.clone(), PROTOCOL_VERSION - 1), None);
+        assert_eq!(downgrade(buf.clone(), PROTOCOL_VERSION), Some(buf));
+    }
+
+    #[test]
+    fn drops_notices_for_versions_before_them() {
+        let buf = encode_notice(NOTICE_ANNOUNCEMENT, NOTICE_INFO, "hello");
+        assert_eq!(downgrade(buf.clone(), 1), None);
+        assert_eq!(downgrade(buf.clone(), 2), None);
+        assert!(downgrade(buf, 3).is_some());
+    }
+
+    #[test]
+    fn keeps_placements_for_version_2() {
+        let downgraded = downgrade(placed(0), 2).unwrap();
+        assert_eq!(version_and_opcode(&downgraded), (2, PIXEL_PLACED_OPCODE));
+    }
+
+    #[test]
+    fn turns_placements_into_pixels_for_version_1() {
+        let downgraded = downgrade(placed(0), 1).unwrap();
+        let bebop_data = decode(&downgraded).unwrap();
+        assert_eq!(
+            (bebop_data.protocol_version, bebop_data.opcode),
+            (1, PIXEL_OPCODE)
+        );
+        let pixel = Pixel::deserialize(&bebop_data.encoded_data).unwrap();
+        assert_eq!((pixel.x, pixel.y), (3, 4));
+        assert_eq!(pixel.color, Color::new(255, 69, 0));
+    }
+
+    #[test]
+    fn turns_placements_on_layers_into_layer_deltas_for_version_1() {
+        let downgraded = downgrade(placed(2), 1).unwrap();
+        let bebop_data = decode(&downgraded).unwrap();
+        assert_eq!(
+            (bebop_data.protocol_version, bebop_data.opcode),
+            (1, LAYER_DELTA_OPCODE)
+        );
+        let delta = LayerDelta::deserialize(&bebop_data.encoded_data).unwrap();
+        assert_eq!(delta.layer, 2);
+        let painted: Vec<Pixel> = delta.painted.iter().collect();
+        assert_eq!(painted.len(), 1);
+        assert_eq!((painted[0].x, painted[0].y), (3, 4));
+        assert!(delta.erased.is_empty());
+    }
+
+    #[test]
+    fn drops_truncated_messages() {
+        assert_eq!(downgrade(vec![1, 0, 0], 1), None);
+    }
+}
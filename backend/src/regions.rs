@@ -0,0 +1,248 @@
+use std::{fmt, fs, io, path::PathBuf, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{auth::Identity, place::Region, session::SessionId, storage};
+
+/// Who may paint inside a protected region.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Grantee {
+    /// An authenticated user, by the subject of their token.
+    User(String),
+    /// Every authenticated user with the role.
+    Role(String),
+    Session(SessionId),
+}
+
+impl Grantee {
+    fn includes(&self, session: SessionId, identity: Option<&Identity>) -> bool {
+        match self {
+            Grantee::User(subject) => identity.is_some_and(|identity| identity.subject == *subject),
+            Grantee::Role(role) => identity.is_some_and(|identity| identity.roles.contains(role)),
+            Grantee::Session(granted) => session == *granted,
+        }
+    }
+}
+
+impl fmt::Display for Grantee {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Grantee::User(subject) => write!(f, "user:{}", subject),
+            Grantee::Role(role) => write!(f, "role:{}", role),
+            Grantee::Session(session) => write!(f, "session:{}", session),
+        }
+    }
+}
+
+impl FromStr for Grantee {
+    type Err = String;
+
+    /// Accepts `user:<subject>`, `role:<role>` or `session:<id>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("user", subject)) if !subject.is_empty() => Ok(Grantee::User(subject.into())),
+            Some(("role", role)) if !role.is_empty() => Ok(Grantee::Role(role.into())),
+            Some(("session", session)) => session
+                .parse()
+                .map(Grantee::Session)
+                .map_err(|_| format!("{:?} is not a session id", session)),
+            _ => Err(format!(
+                "{:?} is not a grantee like user:<subject>, role:<role> or session:<id>",
+                s
+            )),
+        }
+    }
+}
+
+/// A rectangle of the grid only its grantees can paint, moderators aside.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProtectedRegion {
+    pub name: String,
+    pub region: Region,
+    /// Nobody but moderators can paint the region when empty.
+    pub grantees: Vec<Grantee>,
+}
+
+impl fmt::Display for ProtectedRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Region {
+            x,
+            y,
+            width,
+            height,
+        } = self.region;
+        write!(f, "{} at {},{} {}x{}", self.name, x, y, width, height)?;
+        if self.grantees.is_empty() {
+            return write!(f, " for moderators only");
+        }
+        let grantees: Vec<String> = self.grantees.iter().map(Grantee::to_string).collect();
+        write!(f, " for {}", grantees.join(", "))
+    }
+}
+
+/// Protected regions of the canvas, saved to a JSON file after every change.
+pub struct ProtectedRegions {
+    path: PathBuf,
+    regions: Vec<ProtectedRegion>,
+}
+
+impl ProtectedRegions {
+    /// Reads the regions saved at `path`, starting without any when there are none yet.
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let regions = match fs::read(&path) {
+            Ok(contents) => serde_json::from_slice(&contents)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(ProtectedRegions { path, regions })
+    }
+
+    fn save(&self) -> io::Result<()> {
+        storage::save_json(&self.path, &self.regions)
+    }
+
+    /// Adds the region, replacing any previous one with the same name.
+    pub fn add(&mut self, region: ProtectedRegion) -> io::Result<()> {
+        self.regions.retain(|existing| existing.name != region.name);
+        self.regions.push(region);
+        self.save()
+    }
+
+    /// Lifts the protection of the named region, returns `false` if there was none.
+    pub fn remove(&mut self, name: &str) -> io::Result<bool> {
+        let len = self.regions.len();
+        self.regions.retain(|region| region.name != name);
+        if self.regions.len() == len {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Whether the placer may paint the pixel, which takes being granted every region
+    /// covering it.
+    pub fn allows(&self, x: u32, y: u32, session: SessionId, identity: Option<&Identity>) -> bool {
+        self.regions
+            .iter()
+            .filter(|protected| protected.region.contains(x, y))
+            .all(|protected| {
+                protected
+                    .grantees
+                    .iter()
+                    .any(|grantee| grantee.includes(session, identity))
+            })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ProtectedRegion> {
+        self.regions.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protected(name: &str, region: Region, grantees: &[&str]) -> ProtectedRegion {
+        ProtectedRegion {
+            name: name.to_string(),
+            region,
+            grantees: grantees
+                .iter()
+                .map(|grantee| grantee.parse().unwrap())
+                .collect(),
+        }
+    }
+
+    fn regions(regions: Vec<ProtectedRegion>) -> ProtectedRegions {
+        ProtectedRegions {
+            path: PathBuf::new(),
+            regions,
+        }
+    }
+
+    fn identity(subject: &str, roles: &[&str]) -> Identity {
+        Identity {
+            subject: subject.to_string(),
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+        }
+    }
+
+    const SQUARE: Region = Region {
+        x: 10,
+        y: 10,
+        width: 10,
+        height: 10,
+    };
+
+    #[test]
+    fn anyone_paints_outside_every_region() {
+        let regions = regions(vec![protected("square", SQUARE, &[])]);
+        let session = SessionId::random();
+        assert!(regions.allows(9, 10, session, None));
+        assert!(regions.allows(20, 19, session, None));
+        assert!(!regions.allows(10, 10, session, None));
+        assert!(!regions.allows(19, 19, session, None));
+    }
+
+    #[test]
+    fn grants_users_by_subject() {
+        let regions = regions(vec![protected("square", SQUARE, &["user:alice"])]);
+        let session = SessionId::random();
+        assert!(regions.allows(15, 15, session, Some(&identity("alice", &[]))));
+        assert!(!regions.allows(15, 15, session, Some(&identity("bob", &[]))));
+        assert!(!regions.allows(15, 15, session, None));
+    }
+
+    #[test]
+    fn grants_every_user_with_the_role() {
+        let regions = regions(vec![protected("square", SQUARE, &["role:sponsor"])]);
+        let session = SessionId::random();
+        let sponsor = identity("alice", &["team", "sponsor"]);
+        assert!(regions.allows(15, 15, session, Some(&sponsor)));
+        assert!(!regions.allows(15, 15, session, Some(&identity("bob", &["team"]))));
+        // A user named like the role isn't granted through it.
+        assert!(!regions.allows(15, 15, session, Some(&identity("sponsor", &[]))));
+    }
+
+    #[test]
+    fn grants_sessions() {
+        let session = SessionId::random();
+        let grantee = format!("session:{}", session);
+        let regions = regions(vec![protected("square", SQUARE, &[&grantee])]);
+        assert!(regions.allows(15, 15, session, None));
+        assert!(!regions.allows(15, 15, SessionId::random(), None));
+    }
+
+    #[test]
+    fn overlapping_regions_must_all_grant_the_placer() {
+        let overlap = Region {
+            x: 15,
+            y: 15,
+            width: 10,
+            height: 10,
+        };
+        let regions = regions(vec![
+            protected("square", SQUARE, &["role:team", "user:bob"]),
+            protected("overlap", overlap, &["user:alice"]),
+        ]);
+        let session = SessionId::random();
+        let alice = identity("alice", &["team"]);
+        let bob = identity("bob", &[]);
+        assert!(regions.allows(17, 17, session, Some(&alice)));
+        assert!(!regions.allows(17, 17, session, Some(&bob)));
+        assert!(regions.allows(12, 12, session, Some(&bob)));
+        assert!(!regions.allows(22, 22, session, Some(&bob)));
+        assert!(!regions.allows(17, 17, session, Some(&identity("alice", &[]))));
+    }
+
+    #[test]
+    fn parses_grantees() {
+        assert_eq!("user:alice".parse(), Ok(Grantee::User("alice".into())));
+        assert_eq!("role:team".parse(), Ok(Grantee::Role("team".into())));
+        assert!("user:".parse::<Grantee>().is_err());
+        assert!("session:nope".parse::<Grantee>().is_err());
+        assert!("alice".parse::<Grantee>().is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::{fmt, str::FromStr};
+
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Anonymous identity of a client, kept across reconnects through its signed token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct SessionId([u8; 16]);
+
+impl SessionId {
+    pub fn random() -> Self {
+        SessionId(rand::thread_rng().gen())
+    }
+}
+
+impl fmt::Display for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl FromStr for SessionId {
+    type Err = String;
+
+    /// Parses the hex id printed in the logs, or the id part of a session token.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let id = s.split_once('.').map_or(s, |(id, _)| id);
+        hex::decode(id)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(SessionId)
+            .ok_or_else(|| format!("{:?} is not a session id", s))
+    }
+}
+
+impl From<SessionId> for String {
+    fn from(session: SessionId) -> Self {
+        session.to_string()
+    }
+}
+
+impl TryFrom<String> for SessionId {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Issues and checks session tokens, `<session id>.<HMAC-SHA256 of the id>` in hex.
+///
+/// Only the server knows the secret, so a client can't make up a token for someone else's id.
+pub struct SessionKeys {
+    mac: HmacSha256,
+}
+
+impl SessionKeys {
+    pub fn new(secret: &[u8]) -> Self {
+        SessionKeys {
+            mac: HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any size"),
+        }
+    }
+
+    pub fn token(&self, session: SessionId) -> String {
+        let mut mac = self.mac.clone();
+        mac.update(&session.0);
+        format!("{}.{}", session, hex::encode(mac.finalize().into_bytes()))
+    }
+
+    /// Session of an authenticated user, the same one every time they connect.
+    pub fn for_subject(&self, subject: &str) -> SessionId {
+        let mut mac = self.mac.clone();
+        mac.update(b"subject:");
+        mac.update(subject.as_bytes());
+        let mut id = [0; 16];
+        id.copy_from_slice(&mac.finalize().into_bytes()[..16]);
+        SessionId(id)
+    }
+
+    /// Name shown to other players for the session, which can't be turned back into the
+    /// session id banning it or into its token.
+    pub fn public_id(&self, session: SessionId) -> String {
+        let mut mac = self.mac.clone();
+        mac.update(b"public:");
+        mac.update(&session.0);
+        hex::encode(&mac.finalize().into_bytes()[..8])
+    }
+
+    /// Returns the session the token was issued for, `None` if it's malformed or forged.
+    pub fn verify(&self, token: &str) -> Option<SessionId> {
+        let (id, signature) = token.split_once('.')?;
+        let id: [u8; 16] = hex::decode(id).ok()?.try_into().ok()?;
+        let signature = hex::decode(signature).ok()?;
+
+        let mut mac = self.mac.clone();
+        mac.update(&id);
+        mac.verify_slice(&signature).ok()?;
+        Some(SessionId(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_its_own_tokens() {
+        let keys = SessionKeys::new(b"secret");
+        let session = SessionId::random();
+        assert_eq!(keys.verify(&keys.token(session)), Some(session));
+    }
+
+    #[test]
+    fn rejects_tokens_signed_with_another_secret() {
+        let keys = SessionKeys::new(b"secret");
+        let forged = SessionKeys::new(b"guess").token(SessionId::random());
+        assert_eq!(keys.verify(&forged), None);
+    }
+
+    #[test]
+    fn rejects_a_signature_moved_to_another_id() {
+        let keys = SessionKeys::new(b"secret");
+        let token = keys.token(SessionId::random());
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!("{}.{}", SessionId::random(), signature);
+        assert_eq!(keys.verify(&forged), None);
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        let keys = SessionKeys::new(b"secret");
+        let session = SessionId::random();
+        let token = keys.token(session);
+        let (id, signature) = token.split_once('.').unwrap();
+        for malformed in [
+            "",
+            ".",
+            id,
+            &format!("{}.", id),
+            &format!("{}.{}", id, &signature[..signature.len() - 2]),
+            &format!("{}.{}", &id[..30], signature),
+            &format!("{}.zz{}", id, &signature[2..]),
+        ] {
+            assert_eq!(keys.verify(malformed), None, "{:?}", malformed);
+        }
+    }
+
+    #[test]
+    fn session_ids_parse_from_tokens() {
+        let keys = SessionKeys::new(b"secret");
+        let session = SessionId::random();
+        assert_eq!(keys.token(session).parse(), Ok(session));
+        assert_eq!(session.to_string().parse(), Ok(session));
+    }
+}
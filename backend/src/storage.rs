@@ -0,0 +1,11 @@
+use std::{fs, io, path::Path};
+
+use serde::Serialize;
+
+/// Writes the value to `path` as JSON, through a temporary file so a crash can't leave it
+/// truncated.
+pub fn save_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(value)?)?;
+    fs::rename(tmp_path, path)
+}
@@ -0,0 +1,276 @@
+use std::{
+    fmt, fs, io,
+    path::Path,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use bebop::SliceWrapper;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    admin::MAX_GRID_SIZE,
+    canvas,
+    generated::grid::{self, Color, TemplateInfo, TemplateRun, TEMPLATE_OPCODE},
+    place::Region,
+    protocol, storage,
+};
+
+/// Pixels of a template in a row, `None` leaving them transparent.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Run {
+    pub length: u32,
+    pub color: Option<Color>,
+}
+
+impl From<TemplateRun> for Run {
+    fn from(run: TemplateRun) -> Self {
+        Run {
+            length: run.length,
+            color: (run.alpha != 0).then_some(run.color),
+        }
+    }
+}
+
+/// An image players follow when painting a layer, hosted so every client overlays the same.
+#[derive(Serialize, Deserialize)]
+pub struct Template {
+    pub name: String,
+    /// The layer the template is compared with.
+    pub layer: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    runs: Vec<Run>,
+    /// The runs read row by row, rebuilt on load.
+    #[serde(skip)]
+    pixels: Vec<Option<Color>>,
+    /// Opaque pixels inside the grid the layer doesn't match, kept up to date by the state.
+    #[serde(skip)]
+    differing: AtomicU32,
+}
+
+impl Template {
+    /// Builds the template from its runs, read left to right and top to bottom starting a new
+    /// row every `width` pixels.
+    pub fn new(
+        name: String,
+        layer: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        runs: Vec<Run>,
+    ) -> Result<Self, String> {
+        if !canvas::is_valid_name(&name) {
+            return Err(format!(
+                "{:?} is not a template name, use up to 32 letters, digits, - or _",
+                name
+            ));
+        }
+        if !(1..=MAX_GRID_SIZE).contains(&width) {
+            return Err(format!(
+                "a template is between 1 and {} pixels wide",
+                MAX_GRID_SIZE
+            ));
+        }
+        let length: u64 = runs.iter().map(|run| u64::from(run.length)).sum();
+        let height = length.div_ceil(u64::from(width));
+        if !(1..=u64::from(MAX_GRID_SIZE)).contains(&height) {
+            return Err(format!(
+                "a template is between 1 and {} pixels high",
+                MAX_GRID_SIZE
+            ));
+        }
+        if u64::from(x) + u64::from(width) > u64::from(MAX_GRID_SIZE)
+            || u64::from(y) + height > u64::from(MAX_GRID_SIZE)
+        {
+            return Err(format!(
+                "a template has to fit in a canvas of {} by {} pixels",
+                MAX_GRID_SIZE, MAX_GRID_SIZE
+            ));
+        }
+        let mut template = Template {
+            name,
+            layer,
+            x,
+            y,
+            width,
+            height: height as u32,
+            runs,
+            pixels: Vec::new(),
+            differing: AtomicU32::new(0),
+        };
+        template.expand();
+        Ok(template)
+    }
+
+    fn expand(&mut self) {
+        let size = self.width as usize * self.height as usize;
+        self.pixels = self
+            .runs
+            .iter()
+            .flat_map(|run| std::iter::repeat_n(run.color, run.length as usize))
+            .chain(std::iter::repeat(None))
+            .take(size)
+            .collect();
+    }
+
+    pub fn region(&self) -> Region {
+        Region {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// The color the template wants at this position of the grid, `None` where it's
+    /// transparent or doesn't reach.
+    pub fn wanted(&self, x: u32, y: u32) -> Option<Color> {
+        if !self.region().contains(x, y) {
+            return None;
+        }
+        let index = (y - self.y) as usize * self.width as usize + (x - self.x) as usize;
+        self.pixels[index]
+    }
+
+    /// Every opaque pixel with its position on the grid.
+    pub fn opaque_pixels(&self) -> impl Iterator<Item = (u32, u32, Color)> + '_ {
+        self.pixels.iter().enumerate().filter_map(|(index, color)| {
+            let x = self.x + (index % self.width as usize) as u32;
+            let y = self.y + (index / self.width as usize) as u32;
+            color.map(|color| (x, y, color))
+        })
+    }
+
+    pub fn differing(&self) -> u32 {
+        self.differing.load(Ordering::Relaxed)
+    }
+
+    pub fn set_differing(&self, differing: u32) {
+        self.differing.store(differing, Ordering::Relaxed);
+    }
+
+    /// Updates the count after a pixel of the template's layer changed from `before` to
+    /// `after`.
+    pub fn record_change(&self, x: u32, y: u32, before: Option<Color>, after: Option<Color>) {
+        let wanted = match self.wanted(x, y) {
+            Some(wanted) => Some(wanted),
+            None => return,
+        };
+        match (before == wanted, after == wanted) {
+            (true, false) => {
+                self.differing.fetch_add(1, Ordering::Relaxed);
+            }
+            (false, true) => {
+                self.differing.fetch_sub(1, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn info(&self) -> TemplateInfo<'_> {
+        TemplateInfo {
+            name: &self.name,
+            layer: self.layer,
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+            differing: self.differing(),
+        }
+    }
+
+    /// The image as a message for the clients.
+    pub fn encode(&self) -> Vec<u8> {
+        let runs: Vec<TemplateRun> = self
+            .runs
+            .iter()
+            .map(|run| TemplateRun {
+                length: run.length,
+                alpha: if run.color.is_some() { u8::MAX } else { 0 },
+                color: run.color.unwrap_or_default(),
+            })
+            .collect();
+        let template = grid::Template {
+            name: &self.name,
+            layer: self.layer,
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            runs: SliceWrapper::Cooked(&runs),
+        };
+        protocol::encode(TEMPLATE_OPCODE, &template)
+    }
+}
+
+/// Written like `logo on layer 0 at 10,10 32x32, 12 pixels to paint`.
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on layer {} at {},{} {}x{}, {} pixels to paint",
+            self.name,
+            self.layer,
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            self.differing()
+        )
+    }
+}
+
+/// Leaves the image out, it can take megabytes.
+impl fmt::Debug for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Template")
+            .field("name", &self.name)
+            .field("layer", &self.layer)
+            .field("region", &self.region())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads the templates saved at `path`, none when there's no file yet.
+pub fn load(path: &Path) -> io::Result<Vec<Template>> {
+    let mut templates: Vec<Template> = match fs::read(path) {
+        Ok(contents) => serde_json::from_slice(&contents)?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+    for template in &mut templates {
+        template.expand();
+    }
+    Ok(templates)
+}
+
+pub fn save(path: &Path, templates: &[Template]) -> io::Result<()> {
+    storage::save_json(path, templates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(length: u32) -> Vec<Run> {
+        vec![Run {
+            length,
+            color: Some(Color::default()),
+        }]
+    }
+
+    #[test]
+    fn templates_fit_in_the_largest_canvas() {
+        let edge = MAX_GRID_SIZE - 10;
+        assert!(Template::new("logo".into(), 0, edge, edge, 10, runs(100)).is_ok());
+        assert!(Template::new("logo".into(), 0, edge + 1, 0, 10, runs(100)).is_err());
+        assert!(Template::new("logo".into(), 0, 0, edge + 1, 10, runs(100)).is_err());
+    }
+
+    #[test]
+    fn templates_far_off_the_grid_are_refused() {
+        assert!(Template::new("logo".into(), 0, u32::MAX - 5, 0, 10, runs(10)).is_err());
+        assert!(Template::new("logo".into(), 0, 0, u32::MAX, 1, runs(1)).is_err());
+    }
+}
@@ -16,7 +16,9 @@
 // </auto-generated>
 import { BebopView, BebopRuntimeError } from "bebop";
 
-export const ProtocolVersion: number = 1;
+export const ProtocolVersion: number = 3;
+
+export const MinProtocolVersion: number = 1;
 
 export const GridOpcode: number = 1;
 
@@ -24,6 +26,98 @@ export const PixelOpcode: number = 2;
 
 export const DeltaGridOpcode: number = 3;
 
+export const PlacementResultOpcode: number = 4;
+
+export const PixelPlacementOpcode: number = 5;
+
+export const SessionTokenOpcode: number = 6;
+
+export const AdminCommandOpcode: number = 7;
+
+export const AdminResultOpcode: number = 8;
+
+export const PaletteOpcode: number = 9;
+
+export const CanvasScheduleOpcode: number = 10;
+
+export const LayersOpcode: number = 11;
+
+export const LayerGridOpcode: number = 12;
+
+export const LayerDeltaOpcode: number = 13;
+
+export const LayerPixelPlacementOpcode: number = 14;
+
+export const RectFillOpcode: number = 15;
+
+export const PixelListOpcode: number = 16;
+
+export const BitmapOpcode: number = 17;
+
+export const TemplatesOpcode: number = 18;
+
+export const TemplateRequestOpcode: number = 19;
+
+export const TemplateOpcode: number = 20;
+
+export const TemplateUploadOpcode: number = 21;
+
+export const UndoPlacementOpcode: number = 22;
+
+export const PixelPlacedOpcode: number = 23;
+
+export const ClientHelloOpcode: number = 24;
+
+export const ServerHelloOpcode: number = 25;
+
+export const ServerNoticeOpcode: number = 26;
+
+export const PlacementAccepted: number = 0;
+
+export const PlacementServerBusy: number = 1;
+
+export const PlacementOutOfBounds: number = 2;
+
+export const PlacementRateLimited: number = 3;
+
+export const PlacementBanned: number = 4;
+
+export const PlacementCanvasLocked: number = 5;
+
+export const PlacementUnauthorized: number = 6;
+
+export const PlacementInvalidColor: number = 7;
+
+export const PlacementCanvasEnded: number = 8;
+
+export const PlacementProtected: number = 9;
+
+export const PlacementLayerRestricted: number = 10;
+
+export const PlacementNothingToUndo: number = 11;
+
+export const PlacementSpectating: number = 12;
+
+export const NoticePlacementRejected: number = 1;
+
+export const NoticeCooldown: number = 2;
+
+export const NoticeAnnouncement: number = 3;
+
+export const NoticeShutdown: number = 4;
+
+export const NoticeInfo: number = 0;
+
+export const NoticeWarning: number = 1;
+
+export const NoticeError: number = 2;
+
+export const CanvasOpen: number = 0;
+
+export const CanvasLocked: number = 1;
+
+export const CanvasEnded: number = 2;
+
 export interface IBebopData {
   protocolVersion: number;
   opcode: number;
@@ -306,3 +400,1363 @@ export const DeltaGrid = {
   },
 };
 
+export interface IPixelPlacement {
+  requestId: number;
+  pixel: IPixel;
+}
+
+export const PixelPlacement = {
+  encode(message: IPixelPlacement): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IPixelPlacement, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+      Pixel.encodeInto(message.pixel, view)
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IPixelPlacement {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IPixelPlacement {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: IPixel;
+    field1 = Pixel.readFrom(view);
+    let message: IPixelPlacement = {
+      requestId: field0,
+      pixel: field1,
+    };
+    return message;
+  },
+};
+
+export interface IPlacementResult {
+  requestId: number;
+  status: number;
+}
+
+export const PlacementResult = {
+  encode(message: IPlacementResult): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IPlacementResult, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+      view.writeUint32(message.status);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IPlacementResult {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IPlacementResult {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: number;
+    field1 = view.readUint32();
+    let message: IPlacementResult = {
+      requestId: field0,
+      status: field1,
+    };
+    return message;
+  },
+};
+
+export interface ISessionToken {
+  token: string;
+}
+
+export const SessionToken = {
+  encode(message: ISessionToken): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ISessionToken, view: BebopView): number {
+    const before = view.length;
+      view.writeString(message.token);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ISessionToken {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ISessionToken {
+    let field0: string;
+    field0 = view.readString();
+    let message: ISessionToken = {
+      token: field0,
+    };
+    return message;
+  },
+};
+
+export interface IPalette {
+  colors: Array<IColor>;
+}
+
+export const Palette = {
+  encode(message: IPalette): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IPalette, view: BebopView): number {
+    const before = view.length;
+      {
+        const length0 = message.colors.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          Color.encodeInto(message.colors[i0], view)
+        }
+      }
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IPalette {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IPalette {
+    let field0: Array<IColor>;
+    {
+      let length0 = view.readUint32();
+      field0 = new Array<IColor>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: IColor;
+        x0 = Color.readFrom(view);
+        field0[i0] = x0;
+      }
+    }
+    let message: IPalette = {
+      colors: field0,
+    };
+    return message;
+  },
+};
+
+export interface IAdminCommand {
+  requestId: number;
+  command: string;
+}
+
+export const AdminCommand = {
+  encode(message: IAdminCommand): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IAdminCommand, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+      view.writeString(message.command);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IAdminCommand {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IAdminCommand {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: string;
+    field1 = view.readString();
+    let message: IAdminCommand = {
+      requestId: field0,
+      command: field1,
+    };
+    return message;
+  },
+};
+
+export interface IAdminResult {
+  requestId: number;
+  success: boolean;
+  message: string;
+}
+
+export const AdminResult = {
+  encode(message: IAdminResult): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IAdminResult, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+      view.writeBool(message.success);
+      view.writeString(message.message);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IAdminResult {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IAdminResult {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: boolean;
+    field1 = view.readBool();
+    let field2: string;
+    field2 = view.readString();
+    let message: IAdminResult = {
+      requestId: field0,
+      success: field1,
+      message: field2,
+    };
+    return message;
+  },
+};
+
+export interface ICanvasSchedule {
+  status: number;
+  startsAt: bigint;
+  endsAt: bigint;
+}
+
+export const CanvasSchedule = {
+  encode(message: ICanvasSchedule): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ICanvasSchedule, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.status);
+      view.writeUint64(message.startsAt);
+      view.writeUint64(message.endsAt);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ICanvasSchedule {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ICanvasSchedule {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: bigint;
+    field1 = view.readUint64();
+    let field2: bigint;
+    field2 = view.readUint64();
+    let message: ICanvasSchedule = {
+      status: field0,
+      startsAt: field1,
+      endsAt: field2,
+    };
+    return message;
+  },
+};
+
+export interface ILayer {
+  name: string;
+  restricted: boolean;
+}
+
+export const Layer = {
+  encode(message: ILayer): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ILayer, view: BebopView): number {
+    const before = view.length;
+      view.writeString(message.name);
+      view.writeBool(message.restricted);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ILayer {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ILayer {
+    let field0: string;
+    field0 = view.readString();
+    let field1: boolean;
+    field1 = view.readBool();
+    let message: ILayer = {
+      name: field0,
+      restricted: field1,
+    };
+    return message;
+  },
+};
+
+export interface ILayers {
+  layers: Array<ILayer>;
+}
+
+export const Layers = {
+  encode(message: ILayers): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ILayers, view: BebopView): number {
+    const before = view.length;
+      {
+        const length0 = message.layers.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          Layer.encodeInto(message.layers[i0], view)
+        }
+      }
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ILayers {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ILayers {
+    let field0: Array<ILayer>;
+    {
+      let length0 = view.readUint32();
+      field0 = new Array<ILayer>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: ILayer;
+        x0 = Layer.readFrom(view);
+        field0[i0] = x0;
+      }
+    }
+    let message: ILayers = {
+      layers: field0,
+    };
+    return message;
+  },
+};
+
+export interface ILayerGrid {
+  layer: number;
+  pixels: Array<IPixel>;
+}
+
+export const LayerGrid = {
+  encode(message: ILayerGrid): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ILayerGrid, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.layer);
+      {
+        const length0 = message.pixels.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          Pixel.encodeInto(message.pixels[i0], view)
+        }
+      }
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ILayerGrid {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ILayerGrid {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: Array<IPixel>;
+    {
+      let length0 = view.readUint32();
+      field1 = new Array<IPixel>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: IPixel;
+        x0 = Pixel.readFrom(view);
+        field1[i0] = x0;
+      }
+    }
+    let message: ILayerGrid = {
+      layer: field0,
+      pixels: field1,
+    };
+    return message;
+  },
+};
+
+export interface IPosition {
+  x: number;
+  y: number;
+}
+
+export const Position = {
+  encode(message: IPosition): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IPosition, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.x);
+      view.writeUint32(message.y);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IPosition {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IPosition {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: number;
+    field1 = view.readUint32();
+    let message: IPosition = {
+      x: field0,
+      y: field1,
+    };
+    return message;
+  },
+};
+
+export interface ILayerDelta {
+  layer: number;
+  painted: Array<IPixel>;
+  erased: Array<IPosition>;
+}
+
+export const LayerDelta = {
+  encode(message: ILayerDelta): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ILayerDelta, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.layer);
+      {
+        const length0 = message.painted.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          Pixel.encodeInto(message.painted[i0], view)
+        }
+      }
+      {
+        const length0 = message.erased.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          Position.encodeInto(message.erased[i0], view)
+        }
+      }
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ILayerDelta {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ILayerDelta {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: Array<IPixel>;
+    {
+      let length0 = view.readUint32();
+      field1 = new Array<IPixel>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: IPixel;
+        x0 = Pixel.readFrom(view);
+        field1[i0] = x0;
+      }
+    }
+    let field2: Array<IPosition>;
+    {
+      let length0 = view.readUint32();
+      field2 = new Array<IPosition>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: IPosition;
+        x0 = Position.readFrom(view);
+        field2[i0] = x0;
+      }
+    }
+    let message: ILayerDelta = {
+      layer: field0,
+      painted: field1,
+      erased: field2,
+    };
+    return message;
+  },
+};
+
+export interface ILayerPixelPlacement {
+  requestId: number;
+  layer: number;
+  pixel: IPixel;
+}
+
+export const LayerPixelPlacement = {
+  encode(message: ILayerPixelPlacement): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ILayerPixelPlacement, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+      view.writeUint32(message.layer);
+      Pixel.encodeInto(message.pixel, view)
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ILayerPixelPlacement {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ILayerPixelPlacement {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: number;
+    field1 = view.readUint32();
+    let field2: IPixel;
+    field2 = Pixel.readFrom(view);
+    let message: ILayerPixelPlacement = {
+      requestId: field0,
+      layer: field1,
+      pixel: field2,
+    };
+    return message;
+  },
+};
+
+export interface IRectFill {
+  requestId: number;
+  layer: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  color: IColor;
+}
+
+export const RectFill = {
+  encode(message: IRectFill): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IRectFill, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+      view.writeUint32(message.layer);
+      view.writeUint32(message.x);
+      view.writeUint32(message.y);
+      view.writeUint32(message.width);
+      view.writeUint32(message.height);
+      Color.encodeInto(message.color, view)
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IRectFill {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IRectFill {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: number;
+    field1 = view.readUint32();
+    let field2: number;
+    field2 = view.readUint32();
+    let field3: number;
+    field3 = view.readUint32();
+    let field4: number;
+    field4 = view.readUint32();
+    let field5: number;
+    field5 = view.readUint32();
+    let field6: IColor;
+    field6 = Color.readFrom(view);
+    let message: IRectFill = {
+      requestId: field0,
+      layer: field1,
+      x: field2,
+      y: field3,
+      width: field4,
+      height: field5,
+      color: field6,
+    };
+    return message;
+  },
+};
+
+export interface IPixelList {
+  requestId: number;
+  layer: number;
+  pixels: Array<IPixel>;
+}
+
+export const PixelList = {
+  encode(message: IPixelList): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IPixelList, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+      view.writeUint32(message.layer);
+      {
+        const length0 = message.pixels.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          Pixel.encodeInto(message.pixels[i0], view)
+        }
+      }
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IPixelList {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IPixelList {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: number;
+    field1 = view.readUint32();
+    let field2: Array<IPixel>;
+    {
+      let length0 = view.readUint32();
+      field2 = new Array<IPixel>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: IPixel;
+        x0 = Pixel.readFrom(view);
+        field2[i0] = x0;
+      }
+    }
+    let message: IPixelList = {
+      requestId: field0,
+      layer: field1,
+      pixels: field2,
+    };
+    return message;
+  },
+};
+
+export interface IColorRun {
+  length: number;
+  color: IColor;
+}
+
+export const ColorRun = {
+  encode(message: IColorRun): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IColorRun, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.length);
+      Color.encodeInto(message.color, view)
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IColorRun {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IColorRun {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: IColor;
+    field1 = Color.readFrom(view);
+    let message: IColorRun = {
+      length: field0,
+      color: field1,
+    };
+    return message;
+  },
+};
+
+export interface IBitmap {
+  requestId: number;
+  layer: number;
+  x: number;
+  y: number;
+  width: number;
+  runs: Array<IColorRun>;
+}
+
+export const Bitmap = {
+  encode(message: IBitmap): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IBitmap, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+      view.writeUint32(message.layer);
+      view.writeUint32(message.x);
+      view.writeUint32(message.y);
+      view.writeUint32(message.width);
+      {
+        const length0 = message.runs.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          ColorRun.encodeInto(message.runs[i0], view)
+        }
+      }
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IBitmap {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IBitmap {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: number;
+    field1 = view.readUint32();
+    let field2: number;
+    field2 = view.readUint32();
+    let field3: number;
+    field3 = view.readUint32();
+    let field4: number;
+    field4 = view.readUint32();
+    let field5: Array<IColorRun>;
+    {
+      let length0 = view.readUint32();
+      field5 = new Array<IColorRun>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: IColorRun;
+        x0 = ColorRun.readFrom(view);
+        field5[i0] = x0;
+      }
+    }
+    let message: IBitmap = {
+      requestId: field0,
+      layer: field1,
+      x: field2,
+      y: field3,
+      width: field4,
+      runs: field5,
+    };
+    return message;
+  },
+};
+
+export interface ITemplateInfo {
+  name: string;
+  layer: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  differing: number;
+}
+
+export const TemplateInfo = {
+  encode(message: ITemplateInfo): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ITemplateInfo, view: BebopView): number {
+    const before = view.length;
+      view.writeString(message.name);
+      view.writeUint32(message.layer);
+      view.writeUint32(message.x);
+      view.writeUint32(message.y);
+      view.writeUint32(message.width);
+      view.writeUint32(message.height);
+      view.writeUint32(message.differing);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ITemplateInfo {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ITemplateInfo {
+    let field0: string;
+    field0 = view.readString();
+    let field1: number;
+    field1 = view.readUint32();
+    let field2: number;
+    field2 = view.readUint32();
+    let field3: number;
+    field3 = view.readUint32();
+    let field4: number;
+    field4 = view.readUint32();
+    let field5: number;
+    field5 = view.readUint32();
+    let field6: number;
+    field6 = view.readUint32();
+    let message: ITemplateInfo = {
+      name: field0,
+      layer: field1,
+      x: field2,
+      y: field3,
+      width: field4,
+      height: field5,
+      differing: field6,
+    };
+    return message;
+  },
+};
+
+export interface ITemplates {
+  templates: Array<ITemplateInfo>;
+}
+
+export const Templates = {
+  encode(message: ITemplates): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ITemplates, view: BebopView): number {
+    const before = view.length;
+      {
+        const length0 = message.templates.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          TemplateInfo.encodeInto(message.templates[i0], view)
+        }
+      }
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ITemplates {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ITemplates {
+    let field0: Array<ITemplateInfo>;
+    {
+      let length0 = view.readUint32();
+      field0 = new Array<ITemplateInfo>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: ITemplateInfo;
+        x0 = TemplateInfo.readFrom(view);
+        field0[i0] = x0;
+      }
+    }
+    let message: ITemplates = {
+      templates: field0,
+    };
+    return message;
+  },
+};
+
+export interface ITemplateRequest {
+  name: string;
+}
+
+export const TemplateRequest = {
+  encode(message: ITemplateRequest): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ITemplateRequest, view: BebopView): number {
+    const before = view.length;
+      view.writeString(message.name);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ITemplateRequest {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ITemplateRequest {
+    let field0: string;
+    field0 = view.readString();
+    let message: ITemplateRequest = {
+      name: field0,
+    };
+    return message;
+  },
+};
+
+export interface ITemplateRun {
+  length: number;
+  alpha: number;
+  color: IColor;
+}
+
+export const TemplateRun = {
+  encode(message: ITemplateRun): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ITemplateRun, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.length);
+      view.writeByte(message.alpha);
+      Color.encodeInto(message.color, view)
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ITemplateRun {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ITemplateRun {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: number;
+    field1 = view.readByte();
+    let field2: IColor;
+    field2 = Color.readFrom(view);
+    let message: ITemplateRun = {
+      length: field0,
+      alpha: field1,
+      color: field2,
+    };
+    return message;
+  },
+};
+
+export interface ITemplate {
+  name: string;
+  layer: number;
+  x: number;
+  y: number;
+  width: number;
+  runs: Array<ITemplateRun>;
+}
+
+export const Template = {
+  encode(message: ITemplate): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ITemplate, view: BebopView): number {
+    const before = view.length;
+      view.writeString(message.name);
+      view.writeUint32(message.layer);
+      view.writeUint32(message.x);
+      view.writeUint32(message.y);
+      view.writeUint32(message.width);
+      {
+        const length0 = message.runs.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          TemplateRun.encodeInto(message.runs[i0], view)
+        }
+      }
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ITemplate {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ITemplate {
+    let field0: string;
+    field0 = view.readString();
+    let field1: number;
+    field1 = view.readUint32();
+    let field2: number;
+    field2 = view.readUint32();
+    let field3: number;
+    field3 = view.readUint32();
+    let field4: number;
+    field4 = view.readUint32();
+    let field5: Array<ITemplateRun>;
+    {
+      let length0 = view.readUint32();
+      field5 = new Array<ITemplateRun>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: ITemplateRun;
+        x0 = TemplateRun.readFrom(view);
+        field5[i0] = x0;
+      }
+    }
+    let message: ITemplate = {
+      name: field0,
+      layer: field1,
+      x: field2,
+      y: field3,
+      width: field4,
+      runs: field5,
+    };
+    return message;
+  },
+};
+
+export interface ITemplateUpload {
+  requestId: number;
+  canvas: string;
+  template: ITemplate;
+}
+
+export const TemplateUpload = {
+  encode(message: ITemplateUpload): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: ITemplateUpload, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+      view.writeString(message.canvas);
+      Template.encodeInto(message.template, view)
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): ITemplateUpload {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): ITemplateUpload {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: string;
+    field1 = view.readString();
+    let field2: ITemplate;
+    field2 = Template.readFrom(view);
+    let message: ITemplateUpload = {
+      requestId: field0,
+      canvas: field1,
+      template: field2,
+    };
+    return message;
+  },
+};
+
+export interface IUndoPlacement {
+  requestId: number;
+}
+
+export const UndoPlacement = {
+  encode(message: IUndoPlacement): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IUndoPlacement, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.requestId);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IUndoPlacement {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IUndoPlacement {
+    let field0: number;
+    field0 = view.readUint32();
+    let message: IUndoPlacement = {
+      requestId: field0,
+    };
+    return message;
+  },
+};
+
+export interface IPixelPlaced {
+  layer: number;
+  pixel: IPixel;
+  placedAt: bigint;
+  placer: string;
+}
+
+export const PixelPlaced = {
+  encode(message: IPixelPlaced): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IPixelPlaced, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.layer);
+      Pixel.encodeInto(message.pixel, view)
+      view.writeUint64(message.placedAt);
+      view.writeString(message.placer);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IPixelPlaced {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IPixelPlaced {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: IPixel;
+    field1 = Pixel.readFrom(view);
+    let field2: bigint;
+    field2 = view.readUint64();
+    let field3: string;
+    field3 = view.readString();
+    let message: IPixelPlaced = {
+      layer: field0,
+      pixel: field1,
+      placedAt: field2,
+      placer: field3,
+    };
+    return message;
+  },
+};
+
+export interface IClientHello {
+  versions: Array<number>;
+}
+
+export const ClientHello = {
+  encode(message: IClientHello): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IClientHello, view: BebopView): number {
+    const before = view.length;
+      {
+        const length0 = message.versions.length;
+        view.writeUint32(length0);
+        for (let i0 = 0; i0 < length0; i0++) {
+          view.writeUint32(message.versions[i0]);
+        }
+      }
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IClientHello {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IClientHello {
+    let field0: Array<number>;
+    {
+      let length0 = view.readUint32();
+      field0 = new Array<number>(length0);
+      for (let i0 = 0; i0 < length0; i0++) {
+        let x0: number;
+        x0 = view.readUint32();
+        field0[i0] = x0;
+      }
+    }
+    let message: IClientHello = {
+      versions: field0,
+    };
+    return message;
+  },
+};
+
+export interface IServerHello {
+  version: number;
+}
+
+export const ServerHello = {
+  encode(message: IServerHello): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IServerHello, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.version);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IServerHello {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IServerHello {
+    let field0: number;
+    field0 = view.readUint32();
+    let message: IServerHello = {
+      version: field0,
+    };
+    return message;
+  },
+};
+
+export interface IServerNotice {
+  code: number;
+  severity: number;
+  text: string;
+}
+
+export const ServerNotice = {
+  encode(message: IServerNotice): Uint8Array {
+    const view = BebopView.getInstance();
+    view.startWriting();
+    this.encodeInto(message, view);
+    return view.toArray();
+  },
+
+  encodeInto(message: IServerNotice, view: BebopView): number {
+    const before = view.length;
+      view.writeUint32(message.code);
+      view.writeUint32(message.severity);
+      view.writeString(message.text);
+    const after = view.length;
+    return after - before;
+  },
+
+  decode(buffer: Uint8Array): IServerNotice {
+    const view = BebopView.getInstance();
+    view.startReading(buffer);
+    return this.readFrom(view);
+  },
+
+  readFrom(view: BebopView): IServerNotice {
+    let field0: number;
+    field0 = view.readUint32();
+    let field1: number;
+    field1 = view.readUint32();
+    let field2: string;
+    field2 = view.readString();
+    let message: IServerNotice = {
+      code: field0,
+      severity: field1,
+      text: field2,
+    };
+    return message;
+  },
+};
+